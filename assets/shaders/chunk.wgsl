@@ -0,0 +1,68 @@
+#import bevy_pbr::mesh_functions::{get_world_from_local, mesh_position_local_to_clip}
+
+// Must match ChunkMaterial in src/voxel/material.rs
+// xyz: direction light travels in, w: ambient term
+@group(2) @binding(0) var<uniform> sun: vec4<f32>;
+// Linear RGBA per block id
+@group(2) @binding(1) var<uniform> palette: array<vec4<f32>, 256>;
+
+// Must match POSITION_BIAS in src/voxel/mesher.rs
+const POSITION_BIAS: f32 = 8.0;
+
+const NORMALS: array<vec3<f32>, 6> = array<vec3<f32>, 6>(
+    vec3<f32>(1.0, 0.0, 0.0),
+    vec3<f32>(-1.0, 0.0, 0.0),
+    vec3<f32>(0.0, 1.0, 0.0),
+    vec3<f32>(0.0, -1.0, 0.0),
+    vec3<f32>(0.0, 0.0, 1.0),
+    vec3<f32>(0.0, 0.0, -1.0),
+);
+
+struct Vertex {
+    @builtin(instance_index) instance_index: u32,
+    // x: position xyz (8 bits each), normal index (3 bits), ao (2 bits)
+    // y: texture id (8 bits), light (4 bits)
+    @location(0) packed: vec2<u32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+    @location(1) normal: vec3<f32>,
+    @location(2) shade: f32,
+};
+
+@vertex
+fn vertex(vertex: Vertex) -> VertexOutput {
+    let word0 = vertex.packed.x;
+    let word1 = vertex.packed.y;
+
+    let position = vec3<f32>(
+        f32(word0 & 0xFFu),
+        f32((word0 >> 8u) & 0xFFu),
+        f32((word0 >> 16u) & 0xFFu),
+    ) - POSITION_BIAS;
+    let normal_index = (word0 >> 24u) & 0x7u;
+    let ao = f32((word0 >> 27u) & 0x3u);
+    let texture_id = word1 & 0xFFu;
+    let light = f32((word1 >> 8u) & 0xFu);
+
+    let world_from_local = get_world_from_local(vertex.instance_index);
+
+    var out: VertexOutput;
+    out.clip_position = mesh_position_local_to_clip(world_from_local, vec4<f32>(position, 1.0));
+    out.color = palette[texture_id];
+    // Chunks are only ever translated, so the local normal is the world normal
+    var normals = NORMALS;
+    out.normal = normals[normal_index];
+    // Fully occluded corners keep half their brightness
+    out.shade = (0.5 + 0.5 * ao / 3.0) * (light / 15.0);
+    return out;
+}
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    let diffuse = max(dot(normalize(in.normal), -sun.xyz), 0.0);
+    let lighting = sun.w + (1.0 - sun.w) * diffuse;
+    return vec4<f32>(in.color.rgb * lighting * in.shade, in.color.a);
+}
@@ -0,0 +1,32 @@
+use bevy::prelude::*;
+
+/// A line submitted from the console, split on whitespace.
+/// `name` keeps every leading slash but the first, so `//copy` arrives as `/copy`
+#[derive(Event, Debug, Clone)]
+pub struct ConsoleCommand {
+    pub name: String,
+    pub args: Vec<String>,
+}
+
+impl ConsoleCommand {
+    pub fn parse(line: &str) -> Option<Self> {
+        let line = line.trim();
+        let line = line.strip_prefix('/').unwrap_or(line);
+
+        let mut parts = line.split_whitespace();
+        let name = parts.next()?.to_lowercase();
+        Some(Self {
+            name,
+            args: parts.map(str::to_string).collect(),
+        })
+    }
+
+    pub fn arg(&self, index: usize) -> Option<&str> {
+        self.args.get(index).map(String::as_str)
+    }
+
+    /// True when a flag like `-h` was passed anywhere in the arguments
+    pub fn has_flag(&self, flag: &str) -> bool {
+        self.args.iter().any(|a| a == flag)
+    }
+}
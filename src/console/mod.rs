@@ -0,0 +1,5 @@
+pub mod command;
+pub mod ui;
+
+pub use plugin::ConsolePlugin;
+mod plugin;
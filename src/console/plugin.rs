@@ -0,0 +1,19 @@
+use bevy::{input::InputSystem, prelude::*};
+
+use crate::console::{
+    command::ConsoleCommand,
+    ui::{setup_console_ui, type_into_console, update_console_text, ConsoleState}
+};
+
+pub struct ConsolePlugin;
+
+impl Plugin for ConsolePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<ConsoleState>()
+            .add_event::<ConsoleCommand>()
+            .add_systems(Startup, setup_console_ui)
+            .add_systems(PreUpdate, type_into_console.after(InputSystem))
+            .add_systems(Update, update_console_text);
+    }
+}
@@ -0,0 +1,110 @@
+use bevy::{
+    input::{keyboard::{Key, KeyboardInput}, ButtonState},
+    prelude::*,
+};
+
+use crate::console::command::ConsoleCommand;
+
+const FONT_PATH: &str = "fonts/Poppins-Regular.ttf";
+const FONT_SIZE: f32 = 18.0;
+const MAX_INPUT_LENGTH: usize = 256;
+
+#[derive(Resource, Default)]
+pub struct ConsoleState {
+    pub open: bool,
+    pub input: String,
+}
+
+#[derive(Component)]
+pub struct ConsoleText;
+
+/// Run condition for gameplay input that must pause while the player types
+pub fn console_closed(state: Res<ConsoleState>) -> bool {
+    !state.open
+}
+
+pub fn setup_console_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((
+        Text::new(""),
+        TextFont {
+            font: asset_server.load(FONT_PATH),
+            font_size: FONT_SIZE,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.5)),
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(8.0),
+            bottom: Val::Px(8.0),
+            padding: UiRect::all(Val::Px(4.0)),
+            ..default()
+        },
+        Visibility::Hidden,
+        ConsoleText,
+    ));
+}
+
+/// T opens the console, / opens it with a slash typed; Enter submits and Escape cancels.
+/// Runs before gameplay so the keys it uses can be taken away from it
+pub fn type_into_console(
+    mut keys: ResMut<ButtonInput<KeyCode>>,
+    mut state: ResMut<ConsoleState>,
+    mut keyboard: EventReader<KeyboardInput>,
+    mut commands: EventWriter<ConsoleCommand>,
+) {
+    if !state.open {
+        keyboard.clear();
+        if keys.just_pressed(KeyCode::KeyT) || keys.just_pressed(KeyCode::Slash) {
+            state.open = true;
+            state.input = if keys.just_pressed(KeyCode::Slash) { "/".into() } else { String::new() };
+        }
+        return;
+    }
+    // Otherwise Escape would also release the cursor and Enter would respawn
+    keys.clear_just_pressed(KeyCode::Escape);
+    keys.clear_just_pressed(KeyCode::Enter);
+
+    for event in keyboard.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+
+        match &event.logical_key {
+            Key::Enter => {
+                if let Some(command) = ConsoleCommand::parse(&state.input) {
+                    commands.write(command);
+                }
+                state.open = false;
+                state.input.clear();
+                return;
+            }
+            Key::Escape => {
+                state.open = false;
+                state.input.clear();
+                return;
+            }
+            Key::Backspace => {
+                state.input.pop();
+            }
+            Key::Space => state.input.push(' '),
+            Key::Character(text) if state.input.len() < MAX_INPUT_LENGTH => {
+                state.input.extend(text.chars().filter(|c| !c.is_control()));
+            }
+            _ => {}
+        }
+    }
+}
+
+pub fn update_console_text(
+    state: Res<ConsoleState>,
+    mut text: Query<(&mut Text, &mut Visibility), With<ConsoleText>>,
+) {
+    if !state.is_changed() {
+        return;
+    }
+    let Ok((mut text, mut visibility)) = text.single_mut() else { return };
+
+    text.0 = format!("> {}_", state.input);
+    *visibility = if state.open { Visibility::Inherited } else { Visibility::Hidden };
+}
@@ -0,0 +1,5 @@
+pub mod recipe;
+pub mod ui;
+
+pub use plugin::CraftingPlugin;
+mod plugin;
@@ -0,0 +1,28 @@
+use bevy::prelude::*;
+
+use crate::crafting::{
+    recipe::RecipeBook,
+    ui::{
+        click_crafting_slots, crafting_closed, spawn_crafting_ui, toggle_crafting_screen,
+        toggle_recipe_book, update_crafting_slots, use_crafting_table, CraftingScreen
+    }
+};
+use crate::console::ui::console_closed;
+
+pub struct CraftingPlugin;
+
+impl Plugin for CraftingPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<RecipeBook>()
+            .init_resource::<CraftingScreen>()
+            .add_systems(Update, (
+                toggle_crafting_screen.run_if(console_closed),
+                use_crafting_table.run_if(crafting_closed),
+                spawn_crafting_ui,
+                click_crafting_slots,
+                toggle_recipe_book,
+                update_crafting_slots
+            ).chain());
+    }
+}
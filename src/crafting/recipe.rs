@@ -0,0 +1,315 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+use crate::item::{
+    inventory::ItemStack,
+    types::{get_item_by_name, Item}
+};
+
+const RECIPES: &str = include_str!("../../assets/recipes/crafting.txt");
+// Largest grid there is, the crafting table's
+pub const MAX_GRID: usize = 3;
+const EMPTY_CHAR: char = '.';
+
+#[derive(Clone, Debug)]
+pub enum Pattern {
+    /// Cells row by row from the top; None must be left empty
+    Shaped { width: usize, height: usize, cells: Vec<Option<Item>> },
+    /// Ingredients in any arrangement
+    Shapeless(Vec<Item>),
+}
+
+#[derive(Clone, Debug)]
+pub struct Recipe {
+    pub pattern: Pattern,
+    pub result: ItemStack,
+}
+
+impl Recipe {
+    /// Side length of the smallest square grid the recipe fits in
+    pub fn grid_size(&self) -> usize {
+        match &self.pattern {
+            Pattern::Shaped { width, height, .. } => (*width).max(*height),
+            Pattern::Shapeless(items) => if items.len() <= 4 { 2 } else { MAX_GRID },
+        }
+    }
+
+    /// Whether a `size` x `size` grid, row by row from the top, holds exactly this recipe
+    pub fn matches(&self, grid: &[Option<Item>], size: usize) -> bool {
+        match &self.pattern {
+            Pattern::Shaped { width, height, cells } => {
+                // Shaped recipes can sit anywhere in the grid, so only the filled area counts
+                let filled = |x: usize, y: usize| grid[y * size + x].is_some();
+                let (mut min, mut max) = (UVec2::MAX, UVec2::ZERO);
+                for y in 0..size {
+                    for x in (0..size).filter(|&x| filled(x, y)) {
+                        min = min.min(UVec2::new(x as u32, y as u32));
+                        max = max.max(UVec2::new(x as u32, y as u32));
+                    }
+                }
+                if min.x > max.x || (max - min + 1) != UVec2::new(*width as u32, *height as u32) {
+                    return false;
+                }
+
+                let same = |a: Option<Item>, b: Option<Item>| a.map(|i| i.id) == b.map(|i| i.id);
+                let at = |x: usize, y: usize| grid[(min.y as usize + y) * size + min.x as usize + x];
+                let straight = (0..*height).all(|y| (0..*width).all(|x| same(at(x, y), cells[y * width + x])));
+                let mirrored = (0..*height).all(|y| (0..*width).all(|x| same(at(x, y), cells[y * width + width - 1 - x])));
+                straight || mirrored
+            }
+            Pattern::Shapeless(items) => {
+                let mut wanted: Vec<u16> = items.iter().map(|i| i.id).collect();
+                let mut given: Vec<u16> = grid.iter().flatten().map(|i| i.id).collect();
+                wanted.sort_unstable();
+                given.sort_unstable();
+                wanted == given
+            }
+        }
+    }
+
+    /// One line for the recipe book, e.g. "4 Planks: Wood"
+    pub fn describe(&self) -> String {
+        let ingredients = match &self.pattern {
+            Pattern::Shaped { width, height, cells } => {
+                let rows = (0..*height).map(|y| {
+                    (0..*width)
+                        .map(|x| cells[y * width + x].map_or("-", |i| i.name))
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                });
+                rows.collect::<Vec<_>>().join(" / ")
+            }
+            Pattern::Shapeless(items) => items.iter().map(|i| i.name).collect::<Vec<_>>().join(" + "),
+        };
+        format!("{} {}: {}", self.result.count, self.result.item.name, ingredients)
+    }
+}
+
+// A recipe while its lines are still being read
+struct Draft {
+    line: usize,
+    shaped: bool,
+    result: ItemStack,
+    keys: HashMap<char, Item>,
+    rows: Vec<String>,
+    ingredients: Vec<Item>,
+}
+
+impl Draft {
+    fn finish(self) -> Result<Recipe, String> {
+        let err = |msg: &str| format!("recipe on line {}: {}", self.line, msg);
+
+        let pattern = if self.shaped {
+            let height = self.rows.len();
+            let width = self.rows.first().map_or(0, |r| r.chars().count());
+            if height == 0 || height > MAX_GRID || width == 0 || width > MAX_GRID {
+                return Err(err("shape must be between 1x1 and 3x3"));
+            }
+            if self.rows.iter().any(|r| r.chars().count() != width) {
+                return Err(err("rows must all be the same length"));
+            }
+
+            let cells = self.rows.iter()
+                .flat_map(|r| r.chars())
+                .map(|c| match c {
+                    EMPTY_CHAR => Ok(None),
+                    c => self.keys.get(&c).map(|i| Some(*i)).ok_or_else(|| err(&format!("no key for '{}'", c))),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            // Empty edge rows and columns don't count towards the shape
+            let used = |x: usize, y: usize| cells[y * width + x].is_some();
+            let columns: Vec<usize> = (0..width).filter(|&x| (0..height).any(|y| used(x, y))).collect();
+            let rows: Vec<usize> = (0..height).filter(|&y| (0..width).any(|x| used(x, y))).collect();
+            let (Some(&left), Some(&right), Some(&top), Some(&bottom)) =
+                (columns.first(), columns.last(), rows.first(), rows.last())
+            else {
+                return Err(err("shape is empty"));
+            };
+            let trimmed = (top..=bottom)
+                .flat_map(|y| (left..=right).map(move |x| (x, y)))
+                .map(|(x, y)| cells[y * width + x])
+                .collect();
+            Pattern::Shaped { width: right - left + 1, height: bottom - top + 1, cells: trimmed }
+        } else {
+            if self.ingredients.is_empty() || self.ingredients.len() > MAX_GRID * MAX_GRID {
+                return Err(err("needs between 1 and 9 ingredients"));
+            }
+            Pattern::Shapeless(self.ingredients)
+        };
+
+        Ok(Recipe { pattern, result: self.result })
+    }
+}
+
+/// Every known recipe, checked in file order
+#[derive(Resource)]
+pub struct RecipeBook {
+    pub recipes: Vec<Recipe>,
+}
+
+impl Default for RecipeBook {
+    fn default() -> Self {
+        Self::parse(RECIPES).expect("built-in recipes are valid")
+    }
+}
+
+impl RecipeBook {
+    /// Reads the format described at the top of `assets/recipes/crafting.txt`
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut recipes = Vec::new();
+        let mut draft: Option<Draft> = None;
+
+        for (line_no, raw) in text.lines().enumerate() {
+            let line = raw.trim();
+            if line.is_empty() || line.starts_with("//") {
+                continue;
+            }
+            let err = |msg: &str| format!("line {}: {}", line_no + 1, msg);
+            let item = |name: &str| get_item_by_name(name).ok_or_else(|| err(&format!("unknown item '{}'", name)));
+
+            let (directive, rest) = line.split_once(' ').unwrap_or((line, ""));
+            match directive {
+                "shaped" | "shapeless" => {
+                    if let Some(done) = draft.take() {
+                        recipes.push(done.finish()?);
+                    }
+                    let (count, name) = rest.split_once(' ').ok_or_else(|| err("expected <count> <result>"))?;
+                    let result = item(name.trim())?;
+                    let count = count.parse::<u32>()
+                        .ok()
+                        .filter(|c| (1..=result.max_stack).contains(c))
+                        .ok_or_else(|| err("bad result count"))?;
+                    draft = Some(Draft {
+                        line: line_no + 1,
+                        shaped: directive == "shaped",
+                        result: ItemStack::new(result, count),
+                        keys: HashMap::new(),
+                        rows: Vec::new(),
+                        ingredients: Vec::new(),
+                    });
+                }
+                _ => {
+                    let Some(draft) = draft.as_mut() else {
+                        return Err(err("expected shaped or shapeless first"));
+                    };
+                    match directive {
+                        "key" if draft.shaped => {
+                            let (symbol, name) = rest.split_once(' ').ok_or_else(|| err("expected key <char> <item>"))?;
+                            let mut chars = symbol.chars();
+                            let (Some(symbol), None) = (chars.next(), chars.next()) else {
+                                return Err(err("key symbol must be one character"));
+                            };
+                            draft.keys.insert(symbol, item(name.trim())?);
+                        }
+                        "row" if draft.shaped => draft.rows.push(rest.trim().to_string()),
+                        "ingredient" if !draft.shaped => draft.ingredients.push(item(rest.trim())?),
+                        _ => return Err(err(&format!("unexpected '{}'", directive))),
+                    }
+                }
+            }
+        }
+
+        if let Some(done) = draft {
+            recipes.push(done.finish()?);
+        }
+        Ok(Self { recipes })
+    }
+
+    /// The first recipe the grid holds, if any
+    pub fn find(&self, grid: &[Option<Item>], size: usize) -> Option<&Recipe> {
+        self.recipes.iter().find(|r| r.matches(grid, size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::item::types::{STICK, WOODEN_AXE, WOODEN_PICKAXE};
+    use crate::voxel::types::{Block, DIRT, PLANKS, STONE, WOOD};
+
+    fn item(block: Block) -> Option<Item> {
+        Some(Item::from_block(block))
+    }
+
+    fn crafted(book: &RecipeBook, grid: &[Option<Item>], size: usize) -> Option<u16> {
+        book.find(grid, size).map(|r| r.result.item.id)
+    }
+
+    #[test]
+    fn shaped_recipes_match_anywhere_in_the_grid() {
+        let book = RecipeBook::default();
+        let (p, s) = (item(PLANKS), Some(STICK));
+        // The axe pushed into the right two columns of the table
+        let grid = [
+            None, p, p,
+            None, p, s,
+            None, None, s,
+        ];
+        assert_eq!(crafted(&book, &grid, 3), Some(WOODEN_AXE.id));
+        // And the two-high stick in the bottom left corner
+        let grid = [
+            None, None, None,
+            p, None, None,
+            p, None, None,
+        ];
+        assert_eq!(crafted(&book, &grid, 3), Some(STICK.id));
+    }
+
+    #[test]
+    fn shaped_recipes_match_mirrored() {
+        let book = RecipeBook::default();
+        let (p, s) = (item(PLANKS), Some(STICK));
+        let grid = [
+            p, p, None,
+            s, p, None,
+            s, None, None,
+        ];
+        assert_eq!(crafted(&book, &grid, 3), Some(WOODEN_AXE.id));
+    }
+
+    #[test]
+    fn extra_items_reject_a_shaped_recipe() {
+        let book = RecipeBook::default();
+        let (p, s) = (item(PLANKS), Some(STICK));
+        let grid = [
+            p, p, p,
+            None, s, None,
+            item(DIRT), s, None,
+        ];
+        assert_eq!(crafted(&book, &grid, 3), None);
+    }
+
+    #[test]
+    fn shapeless_recipes_match_in_any_order() {
+        let book = RecipeBook::parse("shapeless 1 Stick\ningredient Wood\ningredient Stone\n").unwrap();
+        let (w, s) = (item(WOOD), item(STONE));
+        assert_eq!(crafted(&book, &[w, s, None, None], 2), Some(STICK.id));
+        assert_eq!(crafted(&book, &[None, None, s, w], 2), Some(STICK.id));
+        assert_eq!(crafted(&book, &[None, s, None, None, None, None, None, None, w], 3), Some(STICK.id));
+        assert_eq!(crafted(&book, &[w, w, s, None], 2), None);
+    }
+
+    #[test]
+    fn three_by_three_recipes_never_fit_the_small_grid() {
+        let book = RecipeBook::default();
+        let pickaxe = book.recipes.iter().find(|r| r.result.item.id == WOODEN_PICKAXE.id).unwrap();
+        assert_eq!(pickaxe.grid_size(), 3);
+
+        // Every way of filling the 2x2 grid with planks and sticks
+        let choices = [None, item(PLANKS), Some(STICK)];
+        for n in 0..3usize.pow(4) {
+            let grid: Vec<Option<Item>> = (0..4).map(|i| choices[n / 3usize.pow(i) % 3]).collect();
+            assert!(!pickaxe.matches(&grid, 2));
+        }
+    }
+
+    #[test]
+    fn parse_errors_name_the_problem() {
+        let error = |text: &str| RecipeBook::parse(text).err().unwrap();
+        assert_eq!(error("shapeless 1 Stick\ningredient Cheese\n"), "line 2: unknown item 'Cheese'");
+        assert_eq!(error("shaped 1 Stick\nkey # Planks\nrow #x\n"), "recipe on line 1: no key for 'x'");
+        assert_eq!(error("shaped 1 Stick\nkey # Planks\nrow ##\nrow #\n"), "recipe on line 1: rows must all be the same length");
+    }
+}
@@ -0,0 +1,373 @@
+use bevy::{
+    prelude::*,
+    window::{CursorGrabMode, PrimaryWindow},
+};
+
+use crate::crafting::recipe::{Recipe, RecipeBook, MAX_GRID};
+use crate::item::{
+    drop::{spawn_item_drop, ItemDropAssets},
+    inventory::{Inventory, ItemStack, INVENTORY_SLOTS},
+    types::Item
+};
+use crate::player::{
+    body::{look_ray, Player},
+    camera::CameraController,
+    health::Health,
+    interaction::REACH
+};
+use crate::voxel::{
+    manager::ChunkManager,
+    raycast::raycast,
+    types::CRAFTING_TABLE
+};
+
+const FONT_PATH: &str = "fonts/Poppins-Regular.ttf";
+const FONT_SIZE: f32 = 16.0;
+// Grid the player can craft in without a table
+const INVENTORY_GRID: usize = 2;
+const INVENTORY_COLUMNS: usize = 9;
+const SLOT_SIZE: f32 = 40.0;
+const SLOT_GAP: f32 = 4.0;
+const EMPTY_SLOT: Color = Color::srgba(0.2, 0.2, 0.2, 0.9);
+const PANEL_COLOR: Color = Color::srgba(0.08, 0.08, 0.08, 0.9);
+const UNAVAILABLE_TEXT: Color = Color::srgb(0.5, 0.5, 0.5);
+
+/// The inventory and crafting screen; open is the side of its crafting grid
+#[derive(Resource, Default)]
+pub struct CraftingScreen {
+    pub open: Option<usize>,
+    grid: Vec<Option<ItemStack>>,
+    // Stack picked up with the mouse
+    held: Option<ItemStack>,
+}
+
+impl CraftingScreen {
+    fn grid_items(&self) -> Vec<Option<Item>> {
+        self.grid.iter().map(|slot| slot.map(|s| s.item)).collect()
+    }
+
+    fn recipe<'a>(&self, book: &'a RecipeBook) -> Option<&'a Recipe> {
+        book.find(&self.grid_items(), self.open?)
+    }
+}
+
+/// Run condition for gameplay input that must pause while the screen is up
+pub fn crafting_closed(screen: Res<CraftingScreen>) -> bool {
+    screen.open.is_none()
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum SlotRef {
+    Inventory(usize),
+    Grid(usize),
+    Result,
+}
+
+#[derive(Component)]
+pub struct SlotButton(SlotRef);
+
+#[derive(Component)]
+pub struct SlotCount(SlotRef);
+
+#[derive(Component)]
+pub struct HeldText;
+
+#[derive(Component)]
+pub struct CraftingUi;
+
+#[derive(Component)]
+pub struct RecipeBookButton;
+
+#[derive(Component)]
+pub struct RecipeBookPanel;
+
+fn set_cursor_free(windows: &mut Query<&mut Window, With<PrimaryWindow>>, free: bool) {
+    if let Ok(mut window) = windows.single_mut() {
+        window.cursor_options.visible = free;
+        window.cursor_options.grab_mode = if free { CursorGrabMode::None } else { CursorGrabMode::Locked };
+    }
+}
+
+fn open_screen(screen: &mut CraftingScreen, size: usize, windows: &mut Query<&mut Window, With<PrimaryWindow>>) {
+    screen.open = Some(size);
+    screen.grid = vec![None; size * size];
+    set_cursor_free(windows, true);
+}
+
+/// E opens the inventory with its 2x2 grid and closes whatever is open
+pub fn toggle_crafting_screen(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut screen: ResMut<CraftingScreen>,
+    mut drop_assets: ResMut<ItemDropAssets>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut player: Query<(&Transform, &CameraController, &Health, &mut Inventory), With<Player>>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    let Ok((body, controller, health, mut inventory)) = player.single_mut() else { return };
+
+    if screen.open.is_none() {
+        let grabbed = windows.single().is_ok_and(|w| !w.cursor_options.visible);
+        if keys.just_pressed(KeyCode::KeyE) && grabbed && !health.is_dead() {
+            open_screen(&mut screen, INVENTORY_GRID, &mut windows);
+        }
+        return;
+    }
+    if !keys.just_pressed(KeyCode::KeyE) && !health.is_dead() {
+        return;
+    }
+
+    // Whatever is left on the grid or the mouse goes back, or falls out if there's no room
+    let (eye, look) = look_ray(body, controller);
+    let mut leftovers: Vec<ItemStack> = screen.grid.drain(..).flatten().collect();
+    leftovers.extend(screen.held.take());
+    for stack in leftovers {
+        if let Some(rest) = inventory.add(stack) {
+            spawn_item_drop(&mut commands, &mut drop_assets, &mut materials, rest, eye, look * 2.0);
+        }
+    }
+    screen.open = None;
+    set_cursor_free(&mut windows, false);
+}
+
+/// Right clicking a crafting table opens the 3x3 grid
+pub fn use_crafting_table(
+    mouse: Res<ButtonInput<MouseButton>>,
+    cm: Res<ChunkManager>,
+    mut screen: ResMut<CraftingScreen>,
+    player: Query<(&Transform, &CameraController, &Health), With<Player>>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    if !mouse.just_pressed(MouseButton::Right) || windows.single().is_ok_and(|w| w.cursor_options.visible) {
+        return;
+    }
+    let Ok((body, controller, health)) = player.single() else { return };
+    if health.is_dead() {
+        return;
+    }
+
+    let (eye, look) = look_ray(body, controller);
+    if let Some(hit) = raycast(&cm, eye, look, REACH)
+        && cm.get_block_world(hit.pos).is_some_and(|b| b.id == CRAFTING_TABLE.id)
+    {
+        open_screen(&mut screen, MAX_GRID, &mut windows);
+    }
+}
+
+fn slot(parent: &mut ChildSpawnerCommands, font: &Handle<Font>, slot: SlotRef) {
+    parent
+        .spawn((
+            Button,
+            Node {
+                width: Val::Px(SLOT_SIZE),
+                height: Val::Px(SLOT_SIZE),
+                border: UiRect::all(Val::Px(2.0)),
+                justify_content: JustifyContent::End,
+                align_items: AlignItems::End,
+                ..default()
+            },
+            BorderColor(Color::BLACK),
+            BackgroundColor(EMPTY_SLOT),
+            SlotButton(slot),
+        ))
+        .with_child((
+            Text::new(""),
+            TextFont { font: font.clone(), font_size: FONT_SIZE, ..default() },
+            TextColor(Color::WHITE),
+            SlotCount(slot),
+        ));
+}
+
+fn slot_grid(parent: &mut ChildSpawnerCommands, font: &Handle<Font>, columns: usize, slots: impl Iterator<Item = SlotRef>) {
+    parent
+        .spawn(Node {
+            display: Display::Grid,
+            grid_template_columns: RepeatedGridTrack::px(columns as u16, SLOT_SIZE),
+            column_gap: Val::Px(SLOT_GAP),
+            row_gap: Val::Px(SLOT_GAP),
+            ..default()
+        })
+        .with_children(|grid| {
+            for s in slots {
+                slot(grid, font, s);
+            }
+        });
+}
+
+/// Builds the screen when it opens and removes it once it closes
+pub fn spawn_crafting_ui(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    screen: Res<CraftingScreen>,
+    book: Res<RecipeBook>,
+    existing: Query<Entity, With<CraftingUi>>,
+) {
+    let Some(size) = screen.open else {
+        for entity in &existing {
+            commands.entity(entity).despawn();
+        }
+        return;
+    };
+    if !existing.is_empty() {
+        return;
+    }
+
+    let font: Handle<Font> = asset_server.load(FONT_PATH);
+    let text = |value: &str, color: Color| (
+        Text::new(value),
+        TextFont { font: font.clone(), font_size: FONT_SIZE, ..default() },
+        TextColor(color),
+    );
+    let panel = || (
+        Node {
+            flex_direction: FlexDirection::Column,
+            padding: UiRect::all(Val::Px(12.0)),
+            row_gap: Val::Px(12.0),
+            ..default()
+        },
+        BackgroundColor(PANEL_COLOR),
+    );
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                column_gap: Val::Px(12.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.4)),
+            CraftingUi,
+        ))
+        .with_children(|root| {
+            root.spawn(panel()).with_children(|main| {
+                main.spawn(text(if size == MAX_GRID { "Crafting Table" } else { "Inventory" }, Color::WHITE));
+                main.spawn(Node { align_items: AlignItems::Center, column_gap: Val::Px(12.0), ..default() })
+                    .with_children(|row| {
+                        slot_grid(row, &font, size, (0..size * size).map(SlotRef::Grid));
+                        row.spawn(text("→", Color::WHITE));
+                        slot(row, &font, SlotRef::Result);
+                    });
+                slot_grid(main, &font, INVENTORY_COLUMNS, (0..INVENTORY_SLOTS).map(SlotRef::Inventory));
+                main.spawn((text("", Color::WHITE), HeldText));
+                main.spawn((
+                    Button,
+                    Node { padding: UiRect::axes(Val::Px(8.0), Val::Px(4.0)), ..default() },
+                    BackgroundColor(EMPTY_SLOT),
+                    RecipeBookButton,
+                ))
+                .with_child(text("Recipe Book", Color::WHITE));
+            });
+
+            // Recipes that need a bigger grid than the open one are greyed out
+            root.spawn((panel(), Visibility::Hidden, RecipeBookPanel)).with_children(|list| {
+                list.spawn(text("Recipe Book", Color::WHITE));
+                for recipe in &book.recipes {
+                    let color = if recipe.grid_size() <= size { Color::WHITE } else { UNAVAILABLE_TEXT };
+                    list.spawn(text(&recipe.describe(), color));
+                }
+            });
+        });
+}
+
+// Tops the slot up when they hold the same item and there's room, otherwise swaps it
+// with the mouse, so full stacks and unstackable tools still trade places
+fn click_slot(slot: &mut Option<ItemStack>, held: &mut Option<ItemStack>) {
+    if let (Some(s), Some(h)) = (slot.as_mut(), held.as_mut())
+        && s.item.id == h.item.id
+        && s.count < s.item.max_stack
+    {
+        s.merge(h);
+    } else {
+        std::mem::swap(slot, held);
+    }
+    if held.is_some_and(|h| h.count == 0) {
+        *held = None;
+    }
+}
+
+pub fn click_crafting_slots(
+    book: Res<RecipeBook>,
+    mut screen: ResMut<CraftingScreen>,
+    mut inventory: Query<&mut Inventory, With<Player>>,
+    buttons: Query<(&Interaction, &SlotButton), Changed<Interaction>>,
+) {
+    let Ok(mut inventory) = inventory.single_mut() else { return };
+    let screen = &mut *screen;
+
+    for (interaction, button) in &buttons {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match button.0 {
+            SlotRef::Inventory(i) => click_slot(&mut inventory.slots[i], &mut screen.held),
+            SlotRef::Grid(i) => click_slot(&mut screen.grid[i], &mut screen.held),
+            SlotRef::Result => {
+                let Some(result) = screen.recipe(&book).map(|r| r.result) else { continue };
+                match &mut screen.held {
+                    None => screen.held = Some(result),
+                    Some(h) if h.item.id == result.item.id && h.count + result.count <= h.item.max_stack => {
+                        h.count += result.count;
+                    }
+                    Some(_) => continue,
+                }
+                // Every filled cell gives up one item
+                for cell in &mut screen.grid {
+                    if let Some(stack) = cell {
+                        stack.count -= 1;
+                        if stack.count == 0 {
+                            *cell = None;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub fn toggle_recipe_book(
+    buttons: Query<&Interaction, (Changed<Interaction>, With<RecipeBookButton>)>,
+    mut panel: Query<&mut Visibility, With<RecipeBookPanel>>,
+) {
+    if !buttons.iter().any(|i| *i == Interaction::Pressed) {
+        return;
+    }
+    for mut visibility in &mut panel {
+        *visibility = if *visibility == Visibility::Hidden { Visibility::Inherited } else { Visibility::Hidden };
+    }
+}
+
+/// Shows what is in every slot, the craftable result and the stack on the mouse
+pub fn update_crafting_slots(
+    book: Res<RecipeBook>,
+    screen: Res<CraftingScreen>,
+    inventory: Query<&Inventory, With<Player>>,
+    mut buttons: Query<(&SlotButton, &mut BackgroundColor)>,
+    mut counts: Query<(&SlotCount, &mut Text), Without<HeldText>>,
+    mut held_text: Query<&mut Text, With<HeldText>>,
+) {
+    let Ok(inventory) = inventory.single() else { return };
+    let result = screen.recipe(&book).map(|r| r.result);
+    let stack_in = |slot: SlotRef| match slot {
+        SlotRef::Inventory(i) => inventory.slots[i],
+        SlotRef::Grid(i) => screen.grid.get(i).copied().flatten(),
+        SlotRef::Result => result,
+    };
+
+    for (button, mut background) in &mut buttons {
+        background.0 = stack_in(button.0).map_or(EMPTY_SLOT, |s| s.item.color);
+    }
+    for (count, mut text) in &mut counts {
+        text.0 = stack_in(count.0).filter(|s| s.count > 1).map_or(String::new(), |s| s.count.to_string());
+    }
+    if let Ok(mut text) = held_text.single_mut() {
+        text.0 = match screen.held {
+            Some(s) if s.item.tool.is_some() => format!("Holding {} ({} uses left)", s.item.name, s.durability),
+            Some(s) => format!("Holding {} {}", s.count, s.item.name),
+            None => String::new(),
+        };
+    }
+}
@@ -0,0 +1,259 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+use crate::item::{
+    inventory::{Inventory, ItemStack},
+    types::{Item, APPLE}
+};
+use crate::player::{
+    body::Player,
+    health::Health,
+    movement::PLAYER_HEIGHT
+};
+use crate::voxel::{
+    change::{BlockChangeCause, BlockChanged},
+    loading::WORLD_BOTTOM,
+    manager::ChunkManager,
+    random::WorldRng,
+    types::LEAVES
+};
+
+const ITEM_SIZE: f32 = 0.25;
+// Blocks per second squared
+const ITEM_GRAVITY: f32 = 20.0;
+const TERMINAL_VELOCITY: f32 = 40.0;
+// Fraction of horizontal speed kept per second while resting on the ground
+const GROUND_FRICTION: f32 = 0.02;
+const POP_SPEED: f32 = 4.0;
+// Radians per second
+const SPIN_SPEED: f32 = 1.5;
+const BOB_HEIGHT: f32 = 0.08;
+// Items slide toward players inside the magnet radius and are picked up inside the pickup one
+const MAGNET_RADIUS: f32 = 3.0;
+const MAGNET_SPEED: f32 = 8.0;
+const PICKUP_RADIUS: f32 = 1.0;
+// Seconds before a fresh drop can be picked up, so it's seen popping out
+const PICKUP_DELAY: f32 = 0.5;
+const MERGE_RADIUS: f32 = 0.75;
+const DESPAWN_SECS: f32 = 300.0;
+const VOID_DEPTH: f32 = WORLD_BOTTOM as f32 - 64.0;
+// Chance that broken leaves drop an apple as well
+const APPLE_CHANCE: f32 = 0.125;
+
+/// A stack lying in the world; its Transform is the cube's centre without the bobbing
+#[derive(Component)]
+pub struct ItemDrop {
+    pub stack: ItemStack,
+    pub velocity: Vec3,
+    pub age: f32,
+    on_ground: bool,
+}
+
+/// The visible cube, a child so it can spin and bob without moving the drop itself
+#[derive(Component)]
+pub struct ItemModel;
+
+#[derive(Resource)]
+pub struct ItemDropAssets {
+    mesh: Handle<Mesh>,
+    // One material per item id, shared by every drop of that type
+    materials: HashMap<u16, Handle<StandardMaterial>>,
+}
+
+pub fn setup_item_drop_assets(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    commands.insert_resource(ItemDropAssets {
+        mesh: meshes.add(Cuboid::from_length(ITEM_SIZE)),
+        materials: HashMap::new(),
+    });
+}
+
+pub fn spawn_item_drop(
+    commands: &mut Commands,
+    assets: &mut ItemDropAssets,
+    materials: &mut Assets<StandardMaterial>,
+    stack: ItemStack,
+    position: Vec3,
+    velocity: Vec3,
+) {
+    let material = assets.materials
+        .entry(stack.item.id)
+        .or_insert_with(|| materials.add(StandardMaterial {
+            base_color: stack.item.color,
+            ..Default::default()
+        }))
+        .clone();
+
+    commands
+        .spawn((
+            Transform::from_translation(position),
+            Visibility::default(),
+            ItemDrop { stack, velocity, age: 0.0, on_ground: false },
+        ))
+        .with_child((
+            Mesh3d(assets.mesh.clone()),
+            MeshMaterial3d(material),
+            Transform::default(),
+            ItemModel,
+        ));
+}
+
+/// Blocks broken by the player pop out as a drop of their item, leaves sometimes with an apple
+pub fn drop_broken_blocks(
+    mut commands: Commands,
+    mut events: EventReader<BlockChanged>,
+    mut assets: ResMut<ItemDropAssets>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    for event in events.read() {
+        if event.cause != BlockChangeCause::Player || !event.old.is_solid() || event.new.is_solid() {
+            continue;
+        }
+
+        // Seeded from the position so the same block always pops out the same way
+        let mut rng = WorldRng::new((event.pos.x as u64) << 42 ^ (event.pos.y as u64) << 21 ^ event.pos.z as u64);
+        let spread = Vec2::new(rng.next_f32() - 0.5, rng.next_f32() - 0.5);
+        let velocity = Vec3::new(spread.x, POP_SPEED, spread.y);
+        let position = event.pos.as_vec3() + Vec3::splat(0.5);
+        let stack = ItemStack::new(Item::from_block(event.old), 1);
+        spawn_item_drop(&mut commands, &mut assets, &mut materials, stack, position, velocity);
+
+        if event.old.id == LEAVES.id && rng.next_f32() < APPLE_CHANCE {
+            let apple = ItemStack::new(APPLE, 1);
+            spawn_item_drop(&mut commands, &mut assets, &mut materials, apple, position, velocity * Vec3::new(-1.0, 1.0, -1.0));
+        }
+    }
+}
+
+// Unloaded chunks count as solid so drops wait for the terrain instead of falling through,
+// except below the world bottom where they fall into the void
+fn blocked(cm: &ChunkManager, centre: Vec3) -> bool {
+    let half = Vec3::splat(ITEM_SIZE / 2.0);
+    let min = (centre - half).floor().as_ivec3();
+    let max = (centre + half).floor().as_ivec3();
+
+    (min.y..=max.y).any(|y| {
+        (min.z..=max.z).any(|z| {
+            y >= WORLD_BOTTOM && (min.x..=max.x).any(|x| cm.get_block_world(IVec3::new(x, y, z)).is_none_or(|b| b.is_solid()))
+        })
+    })
+}
+
+pub fn update_item_drops(
+    mut commands: Commands,
+    time: Res<Time>,
+    cm: Res<ChunkManager>,
+    mut drops: Query<(Entity, &mut Transform, &mut ItemDrop)>,
+) {
+    let dt = time.delta_secs().min(0.05);
+
+    for (entity, mut transform, mut drop) in &mut drops {
+        drop.age += dt;
+        if drop.age > DESPAWN_SECS || transform.translation.y < VOID_DEPTH {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        drop.velocity.y = (drop.velocity.y - ITEM_GRAVITY * dt).max(-TERMINAL_VELOCITY);
+        if drop.on_ground {
+            let keep = GROUND_FRICTION.powf(dt);
+            drop.velocity.x *= keep;
+            drop.velocity.z *= keep;
+        }
+
+        // One axis at a time, so a drop slides along walls instead of sticking
+        drop.on_ground = false;
+        for axis in 0..3 {
+            let mut next = transform.translation;
+            next[axis] += drop.velocity[axis] * dt;
+            if blocked(&cm, next) {
+                if axis == 1 && drop.velocity.y < 0.0 {
+                    drop.on_ground = true;
+                }
+                drop.velocity[axis] = 0.0;
+            } else {
+                transform.translation = next;
+            }
+        }
+    }
+}
+
+pub fn animate_item_models(
+    time: Res<Time>,
+    mut models: Query<&mut Transform, With<ItemModel>>,
+) {
+    let t = time.elapsed_secs();
+    for mut transform in &mut models {
+        transform.rotation = Quat::from_rotation_y(t * SPIN_SPEED);
+        transform.translation.y = (t * 2.0).sin() * BOB_HEIGHT;
+    }
+}
+
+/// Combines drops of the same block lying close together, up to a full stack
+pub fn merge_item_drops(
+    mut commands: Commands,
+    mut drops: Query<(Entity, &Transform, &mut ItemDrop)>,
+) {
+    let mut combos = drops.iter_combinations_mut();
+    while let Some([(_, at, mut ad), (b, bt, mut bd)]) = combos.fetch_next() {
+        if ad.stack.count == 0 || bd.stack.count == 0 || ad.stack.item.id != bd.stack.item.id {
+            continue;
+        }
+        if ad.stack.count >= ad.stack.item.max_stack || at.translation.distance(bt.translation) > MERGE_RADIUS {
+            continue;
+        }
+
+        ad.stack.merge(&mut bd.stack);
+        ad.age = ad.age.min(bd.age);
+        if bd.stack.count == 0 {
+            commands.entity(b).despawn();
+        }
+    }
+}
+
+/// Pulls nearby drops toward living players and puts them into their inventory
+pub fn pick_up_item_drops(
+    mut commands: Commands,
+    time: Res<Time>,
+    cm: Res<ChunkManager>,
+    mut players: Query<(&Transform, &Health, &mut Inventory), With<Player>>,
+    mut drops: Query<(Entity, &mut Transform, &mut ItemDrop), Without<Player>>,
+) {
+    for (body, health, mut inventory) in &mut players {
+        if health.is_dead() {
+            continue;
+        }
+        let centre = body.translation + Vec3::Y * PLAYER_HEIGHT / 2.0;
+
+        for (entity, mut transform, mut drop) in &mut drops {
+            if drop.age < PICKUP_DELAY || drop.stack.count == 0 {
+                continue;
+            }
+            let offset = centre - transform.translation;
+            let distance = offset.length();
+
+            if distance < PICKUP_RADIUS {
+                match inventory.add(drop.stack) {
+                    Some(rest) => drop.stack = rest,
+                    None => {
+                        drop.stack.count = 0;
+                        commands.entity(entity).despawn();
+                    }
+                }
+            } else if distance < MAGNET_RADIUS {
+                // Pulled the same way drops fall, so the magnet can't drag them through walls
+                let step = offset / distance * MAGNET_SPEED * time.delta_secs().min(0.05);
+                for axis in 0..3 {
+                    let mut next = transform.translation;
+                    next[axis] += step[axis];
+                    if !blocked(&cm, next) {
+                        transform.translation = next;
+                    }
+                }
+            }
+        }
+    }
+}
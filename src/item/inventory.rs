@@ -0,0 +1,82 @@
+use bevy::prelude::*;
+
+use crate::item::types::Item;
+
+pub const INVENTORY_SLOTS: usize = 36;
+// The first slots double as the hotbar picked with the number keys
+pub const HOTBAR_SLOTS: usize = 9;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ItemStack {
+    pub item: Item,
+    pub count: u32,
+    // Uses left before a tool breaks, 0 for everything else
+    pub durability: u32,
+}
+
+impl ItemStack {
+    pub fn new(item: Item, count: u32) -> Self {
+        Self { item, count, durability: item.tool.map_or(0, |t| t.durability) }
+    }
+
+    /// Moves as much of `other` onto this stack as fits, leaving the rest in it
+    pub fn merge(&mut self, other: &mut ItemStack) {
+        if self.item.id != other.item.id {
+            return;
+        }
+        let moved = other.count.min(self.item.max_stack.saturating_sub(self.count));
+        self.count += moved;
+        other.count -= moved;
+    }
+}
+
+#[derive(Component)]
+pub struct Inventory {
+    pub slots: [Option<ItemStack>; INVENTORY_SLOTS],
+}
+
+impl Default for Inventory {
+    fn default() -> Self {
+        Self { slots: [None; INVENTORY_SLOTS] }
+    }
+}
+
+impl Inventory {
+    /// Tops up matching stacks first, then fills empty slots; returns what didn't fit
+    pub fn add(&mut self, mut stack: ItemStack) -> Option<ItemStack> {
+        for slot in self.slots.iter_mut().flatten() {
+            slot.merge(&mut stack);
+        }
+        for slot in self.slots.iter_mut().filter(|s| s.is_none()) {
+            if stack.count == 0 {
+                break;
+            }
+            let mut placed = ItemStack { count: 0, ..stack };
+            placed.merge(&mut stack);
+            *slot = Some(placed);
+        }
+        (stack.count > 0).then_some(stack)
+    }
+
+    /// Removes one item from the slot, emptying it when that was the last
+    pub fn take_one(&mut self, slot: usize) -> Option<Item> {
+        let stack = self.slots[slot].as_mut()?;
+        let item = stack.item;
+        stack.count -= 1;
+        if stack.count == 0 {
+            self.slots[slot] = None;
+        }
+        Some(item)
+    }
+
+    /// Wears down the tool in the slot by one use; true when that broke it
+    pub fn wear_tool(&mut self, slot: usize) -> bool {
+        let Some(stack) = self.slots[slot].as_mut().filter(|s| s.item.tool.is_some()) else { return false };
+        stack.durability = stack.durability.saturating_sub(1);
+        if stack.durability == 0 {
+            self.slots[slot] = None;
+            return true;
+        }
+        false
+    }
+}
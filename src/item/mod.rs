@@ -0,0 +1,6 @@
+pub mod drop;
+pub mod inventory;
+pub mod types;
+
+pub use plugin::ItemPlugin;
+mod plugin;
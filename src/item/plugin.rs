@@ -0,0 +1,25 @@
+use bevy::prelude::*;
+
+use crate::item::drop::{
+    animate_item_models, drop_broken_blocks, merge_item_drops, pick_up_item_drops,
+    setup_item_drop_assets, update_item_drops
+};
+use crate::player::gamemode::GameMode;
+
+pub struct ItemPlugin;
+
+impl Plugin for ItemPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(Startup, setup_item_drop_assets)
+            .add_systems(Update, (
+                drop_broken_blocks.run_if(in_state(GameMode::Survival)),
+                (
+                    update_item_drops,
+                    merge_item_drops,
+                    pick_up_item_drops.run_if(not(in_state(GameMode::Spectator)))
+                ).chain(),
+                animate_item_models
+            ));
+    }
+}
@@ -0,0 +1,114 @@
+use bevy::prelude::*;
+
+use crate::voxel::types::{Block, BlockCategory, BLOCKS};
+
+pub const DEFAULT_MAX_STACK: u32 = 64;
+// Item ids below this belong to the block with the same id
+const FIRST_ITEM_ID: u16 = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolKind {
+    Pickaxe,
+    Axe,
+    Shovel,
+}
+
+impl ToolKind {
+    /// Blocks this kind of tool is made for
+    pub const fn category(&self) -> BlockCategory {
+        match self {
+            ToolKind::Pickaxe => BlockCategory::Stone,
+            ToolKind::Axe => BlockCategory::Wood,
+            ToolKind::Shovel => BlockCategory::Soil,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tool {
+    pub kind: ToolKind,
+    // Mining speed multiplier on blocks of the tool's category
+    pub speed: f32,
+    // Blocks it can break before it's used up
+    pub durability: u32,
+}
+
+impl Tool {
+    /// How many times faster than by hand this tool mines `block`
+    pub fn speed_on(&self, block: Block) -> f32 {
+        if block.category == self.kind.category() { self.speed } else { 1.0 }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Item {
+    pub id: u16,
+    pub name: &'static str,
+    pub color: Color,
+    pub max_stack: u32,
+    // What using the item places, None for items that can't be placed
+    pub block: Option<Block>,
+    pub tool: Option<Tool>,
+    // Hunger points restored by eating one, 0 for inedible items
+    pub food: u32,
+}
+
+impl Item {
+    pub const fn new(id: u16, name: &'static str, color: Color) -> Self {
+        Self { id, name, color, max_stack: DEFAULT_MAX_STACK, block: None, tool: None, food: 0 }
+    }
+
+    /// The item a block drops as and is placed from
+    pub const fn from_block(block: Block) -> Self {
+        Self {
+            id: block.id as u16,
+            name: block.name,
+            color: block.color,
+            max_stack: DEFAULT_MAX_STACK,
+            block: Some(block),
+            tool: None,
+            food: 0,
+        }
+    }
+
+    pub const fn with_tool(mut self, kind: ToolKind, speed: f32, durability: u32) -> Self {
+        self.tool = Some(Tool { kind, speed, durability });
+        self.max_stack = 1;
+        self
+    }
+
+    pub const fn with_food(mut self, food: u32) -> Self {
+        self.food = food;
+        self
+    }
+}
+
+// Items that aren't blocks
+pub const STICK: Item = Item::new(FIRST_ITEM_ID, "Stick", Color::srgba(0.55, 0.4, 0.22, 1.0));
+pub const WOODEN_PICKAXE: Item = Item::new(FIRST_ITEM_ID + 1, "Wooden Pickaxe", Color::srgba(0.7, 0.55, 0.32, 1.0))
+    .with_tool(ToolKind::Pickaxe, 2.0, 60);
+pub const STONE_PICKAXE: Item = Item::new(FIRST_ITEM_ID + 2, "Stone Pickaxe", Color::srgba(0.45, 0.45, 0.45, 1.0))
+    .with_tool(ToolKind::Pickaxe, 4.0, 132);
+pub const WOODEN_AXE: Item = Item::new(FIRST_ITEM_ID + 3, "Wooden Axe", Color::srgba(0.7, 0.55, 0.32, 1.0))
+    .with_tool(ToolKind::Axe, 2.0, 60);
+pub const STONE_AXE: Item = Item::new(FIRST_ITEM_ID + 4, "Stone Axe", Color::srgba(0.45, 0.45, 0.45, 1.0))
+    .with_tool(ToolKind::Axe, 4.0, 132);
+pub const WOODEN_SHOVEL: Item = Item::new(FIRST_ITEM_ID + 5, "Wooden Shovel", Color::srgba(0.7, 0.55, 0.32, 1.0))
+    .with_tool(ToolKind::Shovel, 2.0, 60);
+pub const STONE_SHOVEL: Item = Item::new(FIRST_ITEM_ID + 6, "Stone Shovel", Color::srgba(0.45, 0.45, 0.45, 1.0))
+    .with_tool(ToolKind::Shovel, 4.0, 132);
+pub const APPLE: Item = Item::new(FIRST_ITEM_ID + 7, "Apple", Color::srgba(0.85, 0.12, 0.1, 1.0)).with_food(4);
+
+// Item registry for easy lookup, block items come from `BLOCKS`
+pub const ITEMS: [Item; 8] = [
+    STICK, WOODEN_PICKAXE, STONE_PICKAXE, WOODEN_AXE, STONE_AXE, WOODEN_SHOVEL, STONE_SHOVEL, APPLE,
+];
+
+/// Finds an item or a block item by name; air and fluids can't be held
+pub fn get_item_by_name(name: &str) -> Option<Item> {
+    let block = BLOCKS.iter()
+        .filter(|b| b.is_solid())
+        .find(|b| b.name.eq_ignore_ascii_case(name))
+        .map(|b| Item::from_block(*b));
+    block.or_else(|| ITEMS.iter().copied().find(|i| i.name.eq_ignore_ascii_case(name)))
+}
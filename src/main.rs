@@ -3,17 +3,29 @@ use bevy::prelude::*;
 mod setup;
 mod voxel;
 mod player;
+mod console;
+mod worldedit;
+mod item;
+mod crafting;
 
 use crate::setup::SetupPlugin;
 use crate::voxel::VoxelPlugin;
 use crate::player::PlayerPlugin;
+use crate::console::ConsolePlugin;
+use crate::worldedit::WorldEditPlugin;
+use crate::item::ItemPlugin;
+use crate::crafting::CraftingPlugin;
 
 fn main() {
     App::new()
         .add_plugins((
             SetupPlugin,
             VoxelPlugin,
-            PlayerPlugin
+            PlayerPlugin,
+            ConsolePlugin,
+            WorldEditPlugin,
+            ItemPlugin,
+            CraftingPlugin
         ))
         .run();
 }
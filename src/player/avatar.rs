@@ -0,0 +1,248 @@
+use bevy::{
+    asset::RenderAssetUsages,
+    image::{ImageLoaderSettings, ImageSampler},
+    prelude::*,
+    render::mesh::{Indices, PrimitiveTopology},
+};
+
+use crate::console::command::ConsoleCommand;
+use crate::player::{
+    body::Player,
+    camera::{CameraController, CameraMode, CameraRig}
+};
+
+const SKIN_DIR: &str = "skins";
+const DEFAULT_SKIN: &str = "default";
+// Skins use the 64x64 layout, one texel per model pixel
+const SKIN_SIZE: f32 = 64.0;
+// The model is 32 pixels tall, scaled to a 1.8 block player
+const PX: f32 = 1.8 / 32.0;
+// Radians of limb swing at full walking speed
+const WALK_SWING: f32 = 0.9;
+// Walk cycle radians per block travelled
+const STRIDE_RATE: f32 = 2.4;
+// Horizontal speed (blocks per second) treated as a full walk
+const FULL_WALK_SPEED: f32 = 4.0;
+const IDLE_SWAY: f32 = 0.05;
+const IDLE_RATE: f32 = 1.5;
+
+/// Drives a blocky model's animation; parts are its direct children
+#[derive(Component, Default)]
+pub struct Avatar {
+    // Walk cycle position, advanced by distance travelled
+    phase: f32,
+    // 0 when standing still, 1 at full walking speed
+    walk: f32,
+    last_position: Option<Vec3>,
+    // Radians, copied from whoever controls this avatar
+    pub head_pitch: f32,
+}
+
+/// Avatar attached to the local player, hidden in first person
+#[derive(Component)]
+pub struct LocalAvatar;
+
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+pub enum AvatarPart {
+    Head,
+    Body,
+    RightArm,
+    LeftArm,
+    RightLeg,
+    LeftLeg,
+}
+
+impl AvatarPart {
+    const ALL: [Self; 6] = [Self::Head, Self::Body, Self::RightArm, Self::LeftArm, Self::RightLeg, Self::LeftLeg];
+
+    // Size in model pixels, the part's skin UV origin, and its pivot above the feet
+    fn layout(self) -> (Vec3, Vec2, Vec3) {
+        match self {
+            Self::Head => (Vec3::splat(8.0), Vec2::new(0.0, 0.0), Vec3::new(0.0, 24.0, 0.0)),
+            Self::Body => (Vec3::new(8.0, 12.0, 4.0), Vec2::new(16.0, 16.0), Vec3::new(0.0, 24.0, 0.0)),
+            Self::RightArm => (Vec3::new(4.0, 12.0, 4.0), Vec2::new(40.0, 16.0), Vec3::new(6.0, 24.0, 0.0)),
+            Self::LeftArm => (Vec3::new(4.0, 12.0, 4.0), Vec2::new(32.0, 48.0), Vec3::new(-6.0, 24.0, 0.0)),
+            Self::RightLeg => (Vec3::new(4.0, 12.0, 4.0), Vec2::new(0.0, 16.0), Vec3::new(2.0, 12.0, 0.0)),
+            Self::LeftLeg => (Vec3::new(4.0, 12.0, 4.0), Vec2::new(16.0, 48.0), Vec3::new(-2.0, 12.0, 0.0)),
+        }
+    }
+
+    // The head sits on its pivot, everything else hangs from it
+    fn hangs(self) -> bool {
+        self != Self::Head
+    }
+}
+
+/// Cuboid with Minecraft-style box UVs: the six faces unfold around `uv` on the skin,
+/// with the front facing -Z. `hangs` puts the pivot on the top face instead of the bottom
+fn skin_cuboid(size: Vec3, uv: Vec2, hangs: bool) -> Mesh {
+    let (w, h, d) = (size.x, size.y, size.z);
+    let min = Vec3::new(-w / 2.0, if hangs { -h } else { 0.0 }, -d / 2.0) * PX;
+    let max = min + size * PX;
+    let (x0, y0, z0, x1, y1, z1) = (min.x, min.y, min.z, max.x, max.y, max.z);
+
+    // Corners top-left, top-right, bottom-right, bottom-left as seen from outside, and the UV rect
+    let faces = [
+        // Front (-Z)
+        ([[x1, y1, z0], [x0, y1, z0], [x0, y0, z0], [x1, y0, z0]], [0.0, 0.0, -1.0], [uv.x + d, uv.y + d, w, h]),
+        // Back (+Z)
+        ([[x0, y1, z1], [x1, y1, z1], [x1, y0, z1], [x0, y0, z1]], [0.0, 0.0, 1.0], [uv.x + 2.0 * d + w, uv.y + d, w, h]),
+        // Right (+X)
+        ([[x1, y1, z1], [x1, y1, z0], [x1, y0, z0], [x1, y0, z1]], [1.0, 0.0, 0.0], [uv.x, uv.y + d, d, h]),
+        // Left (-X)
+        ([[x0, y1, z0], [x0, y1, z1], [x0, y0, z1], [x0, y0, z0]], [-1.0, 0.0, 0.0], [uv.x + d + w, uv.y + d, d, h]),
+        // Top (+Y)
+        ([[x1, y1, z1], [x0, y1, z1], [x0, y1, z0], [x1, y1, z0]], [0.0, 1.0, 0.0], [uv.x + d, uv.y, w, d]),
+        // Bottom (-Y)
+        ([[x0, y0, z1], [x1, y0, z1], [x1, y0, z0], [x0, y0, z0]], [0.0, -1.0, 0.0], [uv.x + d + w, uv.y, w, d]),
+    ];
+
+    let mut positions = Vec::with_capacity(24);
+    let mut normals = Vec::with_capacity(24);
+    let mut uvs = Vec::with_capacity(24);
+    let mut indices = Vec::with_capacity(36);
+
+    for (corners, normal, [u, v, uw, vh]) in faces {
+        let base = positions.len() as u32;
+        positions.extend(corners);
+        normals.extend([normal; 4]);
+        uvs.extend([[u, v], [u + uw, v], [u + uw, v + vh], [u, v + vh]].map(|[a, b]| [a / SKIN_SIZE, b / SKIN_SIZE]));
+        indices.extend([base, base + 2, base + 1, base, base + 3, base + 2]);
+    }
+
+    Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default())
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
+        .with_inserted_indices(Indices::U32(indices))
+}
+
+fn load_skin(asset_server: &AssetServer, name: &str) -> Handle<Image> {
+    // Nearest sampling keeps skin pixels crisp
+    asset_server.load_with_settings(format!("{}/{}.png", SKIN_DIR, name), |settings: &mut ImageLoaderSettings| {
+        settings.sampler = ImageSampler::nearest();
+    })
+}
+
+fn skin_material(skin: Handle<Image>) -> StandardMaterial {
+    StandardMaterial {
+        base_color_texture: Some(skin),
+        alpha_mode: AlphaMode::Mask(0.5),
+        perceptual_roughness: 1.0,
+        ..default()
+    }
+}
+
+/// Builds an avatar model as a child of `parent`, whose Transform is at the feet
+pub fn spawn_avatar(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    material: Handle<StandardMaterial>,
+    parent: Entity,
+) -> Entity {
+    let root = commands.spawn((Transform::default(), Visibility::default(), Avatar::default())).id();
+    commands.entity(parent).add_child(root);
+
+    for part in AvatarPart::ALL {
+        let (size, uv, pivot) = part.layout();
+        commands.spawn((
+            Mesh3d(meshes.add(skin_cuboid(size, uv, part.hangs()))),
+            MeshMaterial3d(material.clone()),
+            Transform::from_translation(pivot * PX),
+            part,
+            ChildOf(root),
+        ));
+    }
+
+    root
+}
+
+pub fn spawn_player_avatar(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    asset_server: Res<AssetServer>,
+    player: Query<Entity, With<Player>>,
+) {
+    let Ok(player) = player.single() else { return };
+    let material = materials.add(skin_material(load_skin(&asset_server, DEFAULT_SKIN)));
+    let avatar = spawn_avatar(&mut commands, &mut meshes, material, player);
+    commands.entity(avatar).insert(LocalAvatar);
+}
+
+/// `/skin <name>` swaps the local avatar's texture for `assets/skins/<name>.png`
+pub fn change_skin(
+    mut commands: EventReader<ConsoleCommand>,
+    asset_server: Res<AssetServer>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    avatar: Query<&Children, With<LocalAvatar>>,
+    parts: Query<&MeshMaterial3d<StandardMaterial>, With<AvatarPart>>,
+) {
+    for command in commands.read().filter(|c| c.name == "skin") {
+        let Some(name) = command.arg(0) else {
+            warn!("⚠️ /skin: missing skin name");
+            continue;
+        };
+        if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+            warn!("⚠️ /skin: skin names may only use letters, digits, '_' and '-'");
+            continue;
+        }
+
+        // Every part shares one material, so updating it through any part reskins the whole model
+        let material = avatar.single().ok()
+            .and_then(|children| children.iter().find_map(|child| parts.get(child).ok()))
+            .and_then(|handle| materials.get_mut(&handle.0));
+        if let Some(material) = material {
+            material.base_color_texture = Some(load_skin(&asset_server, name));
+            info!("👕 Skin set to {}", name);
+        }
+    }
+}
+
+/// Copies head pitch from the camera controller and hides the model in first person
+pub fn sync_local_avatar(
+    player: Query<&CameraController, With<Player>>,
+    rig: Query<&CameraRig>,
+    mut avatar: Query<(&mut Avatar, &mut Visibility), With<LocalAvatar>>,
+) {
+    let (Ok(controller), Ok((mut avatar, mut visibility))) = (player.single(), avatar.single_mut()) else { return };
+    avatar.head_pitch = controller.pitch;
+
+    let first_person = rig.single().is_ok_and(|r| r.mode == CameraMode::FirstPerson);
+    visibility.set_if_neq(if first_person { Visibility::Hidden } else { Visibility::Inherited });
+}
+
+/// Swings arms and legs with horizontal speed, and sways them gently when standing still
+pub fn animate_avatars(
+    time: Res<Time>,
+    mut avatars: Query<(&mut Avatar, &GlobalTransform)>,
+    mut parts: Query<(&AvatarPart, &ChildOf, &mut Transform)>,
+) {
+    let dt = time.delta_secs();
+    for (mut avatar, transform) in avatars.iter_mut() {
+        let position = transform.translation();
+        let moved = avatar.last_position.map_or(0.0, |last| (position - last).xz().length());
+        avatar.last_position = Some(position);
+
+        let speed = if dt > 0.0 { moved / dt } else { 0.0 };
+        // Eased so stopping and starting don't snap the limbs
+        let target = (speed / FULL_WALK_SPEED).min(1.0);
+        avatar.walk += (target - avatar.walk) * (1.0 - (-10.0 * dt).exp());
+        avatar.phase += moved * STRIDE_RATE;
+    }
+
+    let idle = (time.elapsed_secs() * IDLE_RATE).sin() * IDLE_SWAY;
+    for (part, child_of, mut transform) in parts.iter_mut() {
+        let Ok((avatar, _)) = avatars.get(child_of.parent()) else { continue };
+        let swing = avatar.phase.sin() * WALK_SWING * avatar.walk;
+
+        transform.rotation = match part {
+            AvatarPart::Head => Quat::from_rotation_x(avatar.head_pitch),
+            AvatarPart::Body => Quat::IDENTITY,
+            AvatarPart::RightArm => Quat::from_rotation_x(-swing) * Quat::from_rotation_z(idle),
+            AvatarPart::LeftArm => Quat::from_rotation_x(swing) * Quat::from_rotation_z(-idle),
+            AvatarPart::RightLeg => Quat::from_rotation_x(swing),
+            AvatarPart::LeftLeg => Quat::from_rotation_x(-swing),
+        };
+    }
+}
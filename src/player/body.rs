@@ -0,0 +1,58 @@
+use bevy::prelude::*;
+
+use crate::player::{
+    camera::{CameraController, CameraRig, PlayerCamera, BASE_FOV},
+    health::Health,
+    hunger::Hunger,
+    movement::{PlayerMotion, SENSITIVITY}
+};
+use crate::item::inventory::Inventory;
+use crate::voxel::generation::WorldGenerator;
+
+// From the feet, where the body's Transform sits, to the eyes
+pub const EYE_HEIGHT: f32 = 1.62;
+
+/// The player's body; its Transform is at the feet and only ever turns around Y.
+/// Head look lives in its `CameraController`
+#[derive(Component)]
+pub struct Player;
+
+/// Feet position players start at and respawn to
+#[derive(Resource)]
+pub struct WorldSpawn(pub Vec3);
+
+/// Eye position and look direction, whatever the camera is doing
+pub fn look_ray(transform: &Transform, controller: &CameraController) -> (Vec3, Vec3) {
+    (transform.translation + Vec3::Y * EYE_HEIGHT, controller.rotation() * Vec3::NEG_Z)
+}
+
+pub fn spawn_player(mut commands: Commands, generator: Res<WorldGenerator>) {
+    // Standing on top of the world origin's column, in the middle of the block
+    let spawn = Vec3::new(0.5, generator.surface_height(0, 0) as f32 + 1.0, 0.5);
+    let eye = spawn + Vec3::Y * EYE_HEIGHT;
+    let controller = CameraController::from_rotation(Quat::IDENTITY, SENSITIVITY);
+    let facing = Transform::from_translation(eye).with_rotation(controller.rotation());
+
+    commands.insert_resource(WorldSpawn(spawn));
+    commands.spawn((
+        Transform::from_translation(spawn),
+        Visibility::default(),
+        controller,
+        PlayerMotion::default(),
+        Health::default(),
+        Hunger::default(),
+        Inventory::default(),
+        Player,
+    ));
+
+    commands.spawn((
+        Camera3d::default(),
+        Projection::Perspective(PerspectiveProjection {
+            fov: BASE_FOV,
+            ..default()
+        }),
+        Transform::from_translation(eye).with_rotation(facing.rotation),
+        CameraRig::default(),
+        PlayerCamera,
+    ));
+}
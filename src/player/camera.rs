@@ -1,12 +1,217 @@
 use bevy::prelude::*;
 
+use crate::console::command::ConsoleCommand;
+use crate::player::body::{look_ray, Player};
+use crate::voxel::{manager::ChunkManager, raycast::raycast};
+
+// Just short of straight up/down, where yaw stops being well defined
+const MAX_PITCH: f32 = 89.0_f32.to_radians();
+pub const BASE_FOV: f32 = 70.0_f32.to_radians();
+const SPRINT_FOV: f32 = 80.0_f32.to_radians();
+// Longest look smoothing `/smoothing` accepts, in seconds
+const MAX_LOOK_SMOOTHING: f32 = 0.5;
+// How quickly the FOV eases toward its target, per second
+const FOV_EASING: f32 = 8.0;
+// Third-person arm length when nothing is in the way
+const ARM_LENGTH: f32 = 4.0;
+// Kept between the camera and any block it backs into, so the near plane doesn't cut through
+const CAMERA_RADIUS: f32 = 0.25;
+// How quickly the arm extends again once an obstacle is gone, per second
+const ARM_EASING: f32 = 6.0;
+
+/// Rendering camera; follows the player according to its `CameraRig`
 #[derive(Component)]
 pub struct PlayerCamera;
 
-pub fn spawn_player_camera(mut commands: Commands) {
-    commands.spawn((
-        Camera3d::default(),
-        Transform::from_xyz(20.0, 20.0, 20.0).looking_at(Vec3::ZERO, Vec3::Y),
-        PlayerCamera,
-    ));
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum CameraMode {
+    #[default]
+    FirstPerson,
+    // Over the shoulder, looking where the player looks
+    ThirdPersonBehind,
+    // In front of the player, looking back at their face
+    ThirdPersonFront,
+    // Circles the player with the mouse while the body stays put
+    Orbit,
+}
+
+impl CameraMode {
+    fn next(self) -> Self {
+        match self {
+            Self::FirstPerson => Self::ThirdPersonBehind,
+            Self::ThirdPersonBehind => Self::ThirdPersonFront,
+            Self::ThirdPersonFront => Self::Orbit,
+            Self::Orbit => Self::FirstPerson,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct CameraRig {
+    pub mode: CameraMode,
+    // Current spring arm length, shortened whenever terrain is in the way
+    arm: f32,
+    orbit_yaw: f32,
+    orbit_pitch: f32,
+}
+
+impl Default for CameraRig {
+    fn default() -> Self {
+        Self { mode: CameraMode::FirstPerson, arm: 0.0, orbit_yaw: 0.0, orbit_pitch: 0.0 }
+    }
+}
+
+impl CameraRig {
+    pub fn add_orbit_look(&mut self, delta: Vec2, sensitivity: f32) {
+        self.orbit_yaw -= delta.x * sensitivity;
+        self.orbit_pitch = (self.orbit_pitch - delta.y * sensitivity).clamp(-MAX_PITCH, MAX_PITCH);
+    }
+}
+
+/// Head look state of the player body; rotations are rebuilt from yaw and pitch every frame,
+/// so they never pick up roll
+#[derive(Component)]
+pub struct CameraController {
+    // Radians, around world Y
+    pub yaw: f32,
+    // Radians, clamped to ±89°
+    pub pitch: f32,
+    // Where mouse input has pushed yaw/pitch; the actual angles follow it when smoothing
+    pub target_yaw: f32,
+    pub target_pitch: f32,
+    // Radians per pixel of mouse motion
+    pub sensitivity: f32,
+    // Seconds to close most of the gap to the target, 0 turns smoothing off
+    pub smoothing: f32,
+    // Set by movement while sprinting, widens the FOV
+    pub sprinting: bool,
+}
+
+impl CameraController {
+    pub fn from_rotation(rotation: Quat, sensitivity: f32) -> Self {
+        let (yaw, pitch, _) = rotation.to_euler(EulerRot::YXZ);
+        let pitch = pitch.clamp(-MAX_PITCH, MAX_PITCH);
+        Self {
+            yaw,
+            pitch,
+            target_yaw: yaw,
+            target_pitch: pitch,
+            sensitivity,
+            smoothing: 0.0,
+            sprinting: false,
+        }
+    }
+
+    pub fn add_look(&mut self, delta: Vec2) {
+        self.target_yaw -= delta.x * self.sensitivity;
+        self.target_pitch = (self.target_pitch - delta.y * self.sensitivity).clamp(-MAX_PITCH, MAX_PITCH);
+    }
+
+    /// Moves the angles toward their targets
+    pub fn update(&mut self, dt: f32) {
+        let t = if self.smoothing > 0.0 { 1.0 - (-dt / self.smoothing).exp() } else { 1.0 };
+        self.yaw += (self.target_yaw - self.yaw) * t;
+        self.pitch += (self.target_pitch - self.pitch) * t;
+    }
+
+    pub fn rotation(&self) -> Quat {
+        Quat::from_euler(EulerRot::YXZ, self.yaw, self.pitch, 0.0)
+    }
+}
+
+/// `/smoothing <seconds>` sets how long the view takes to catch up with the mouse, 0 for none
+pub fn set_look_smoothing(
+    mut commands: EventReader<ConsoleCommand>,
+    mut player: Query<&mut CameraController, With<Player>>,
+) {
+    for command in commands.read().filter(|c| c.name == "smoothing") {
+        let Some(seconds) = command.arg(0).and_then(|a| a.parse::<f32>().ok()) else {
+            warn!("⚠️ /smoothing: expected a number of seconds");
+            continue;
+        };
+        if !(0.0..=MAX_LOOK_SMOOTHING).contains(&seconds) {
+            warn!("⚠️ /smoothing: must be between 0 and {} seconds", MAX_LOOK_SMOOTHING);
+            continue;
+        }
+        if let Ok(mut controller) = player.single_mut() {
+            controller.smoothing = seconds;
+            info!("🎥 Look smoothing set to {}s", seconds);
+        }
+    }
+}
+
+/// F5 cycles first person, third person behind, third person front and orbit
+pub fn cycle_camera_mode(
+    keys: Res<ButtonInput<KeyCode>>,
+    player: Query<&CameraController, With<Player>>,
+    mut rig: Query<&mut CameraRig>,
+) {
+    if !keys.just_pressed(KeyCode::F5) {
+        return;
+    }
+    let (Ok(controller), Ok(mut rig)) = (player.single(), rig.single_mut()) else { return };
+
+    rig.mode = rig.mode.next();
+    if rig.mode == CameraMode::Orbit {
+        // Start orbiting from behind the player
+        rig.orbit_yaw = controller.yaw;
+        rig.orbit_pitch = controller.pitch;
+    }
+    info!("🎥 Camera mode: {:?}", rig.mode);
+}
+
+/// Places the camera for its mode. In third person a spring arm is cast from the eyes
+/// toward the camera and snaps in when it meets terrain, easing back out once clear
+pub fn update_camera_rig(
+    time: Res<Time>,
+    cm: Res<ChunkManager>,
+    player: Query<(&Transform, &CameraController), With<Player>>,
+    // CameraRig only lives on the camera; Without keeps the two Transform queries disjoint
+    mut camera: Query<(&mut Transform, &mut CameraRig), Without<Player>>,
+) {
+    let (Ok((body, controller)), Ok((mut transform, mut rig))) = (player.single(), camera.single_mut()) else {
+        return;
+    };
+    let (eye, _) = look_ray(body, controller);
+
+    let arm_rotation = match rig.mode {
+        CameraMode::FirstPerson => {
+            rig.arm = 0.0;
+            *transform = Transform::from_translation(eye).with_rotation(controller.rotation());
+            return;
+        }
+        CameraMode::ThirdPersonBehind => controller.rotation(),
+        CameraMode::ThirdPersonFront => controller.rotation() * Quat::from_rotation_y(std::f32::consts::PI),
+        CameraMode::Orbit => Quat::from_euler(EulerRot::YXZ, rig.orbit_yaw, rig.orbit_pitch, 0.0),
+    };
+    let arm_dir = arm_rotation * Vec3::Z;
+
+    let clear = raycast(&cm, eye, arm_dir, ARM_LENGTH + CAMERA_RADIUS)
+        .map_or(ARM_LENGTH, |hit| (hit.distance - CAMERA_RADIUS).max(0.0));
+    rig.arm = if clear < rig.arm {
+        clear
+    } else {
+        rig.arm + (clear - rig.arm) * (1.0 - (-ARM_EASING * time.delta_secs()).exp())
+    };
+
+    transform.translation = eye + arm_dir * rig.arm;
+    // Behind the player the camera looks where they look, otherwise at their eyes
+    transform.rotation = match rig.mode {
+        CameraMode::ThirdPersonBehind => controller.rotation(),
+        _ => Transform::from_translation(transform.translation).looking_at(eye, Vec3::Y).rotation,
+    };
+}
+
+/// Eases the field of view out while sprinting and back in afterwards
+pub fn update_sprint_fov(
+    time: Res<Time>,
+    player: Query<&CameraController, With<Player>>,
+    mut camera: Query<&mut Projection, With<PlayerCamera>>,
+) {
+    let (Ok(controller), Ok(mut projection)) = (player.single(), camera.single_mut()) else { return };
+    let Projection::Perspective(perspective) = projection.as_mut() else { return };
+
+    let target = if controller.sprinting { SPRINT_FOV } else { BASE_FOV };
+    let t = 1.0 - (-FOV_EASING * time.delta_secs()).exp();
+    perspective.fov += (target - perspective.fov) * t;
 }
@@ -0,0 +1,99 @@
+use std::fs;
+
+use bevy::prelude::*;
+
+use crate::console::command::ConsoleCommand;
+
+const SAVE_DIR: &str = "saves/players";
+const DEFAULT_PLAYER_NAME: &str = "player";
+
+/// Rules the local player plays by; gameplay systems are gated on it with run conditions
+#[derive(States, Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum GameMode {
+    // Health, block hardness and drops
+    #[default]
+    Survival,
+    // Instant breaking, unlimited blocks, flight and the building tools
+    Creative,
+    // Noclip flight, no interaction with the world
+    Spectator,
+}
+
+impl GameMode {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Survival => "survival",
+            Self::Creative => "creative",
+            Self::Spectator => "spectator",
+        }
+    }
+
+    fn parse(text: &str) -> Option<Self> {
+        match text.to_lowercase().as_str() {
+            "survival" | "s" | "0" => Some(Self::Survival),
+            "creative" | "c" | "1" => Some(Self::Creative),
+            "spectator" | "sp" | "3" => Some(Self::Spectator),
+            _ => None,
+        }
+    }
+}
+
+/// Identifies the local player's save file
+#[derive(Resource)]
+pub struct PlayerProfile {
+    pub name: String,
+}
+
+impl Default for PlayerProfile {
+    fn default() -> Self {
+        Self { name: DEFAULT_PLAYER_NAME.to_string() }
+    }
+}
+
+impl PlayerProfile {
+    fn save_path(&self) -> String {
+        format!("{}/{}.txt", SAVE_DIR, self.name)
+    }
+}
+
+// Save files hold one `key value` pair per line
+fn read_saved_mode(text: &str) -> Option<GameMode> {
+    text.lines()
+        .filter_map(|line| line.trim().split_once(' '))
+        .find(|(key, _)| *key == "game_mode")
+        .and_then(|(_, value)| GameMode::parse(value.trim()))
+}
+
+pub fn load_game_mode(profile: Res<PlayerProfile>, mut next: ResMut<NextState<GameMode>>) {
+    let Ok(text) = fs::read_to_string(profile.save_path()) else { return };
+    match read_saved_mode(&text) {
+        Some(mode) => next.set(mode),
+        None => warn!("⚠️ No valid game mode in {}", profile.save_path()),
+    }
+}
+
+/// Writes the mode whenever it changes, so the player comes back in it next session
+pub fn save_game_mode(profile: Res<PlayerProfile>, mode: Res<State<GameMode>>) {
+    let path = profile.save_path();
+    let result = fs::create_dir_all(SAVE_DIR)
+        .and_then(|_| fs::write(&path, format!("game_mode {}\n", mode.get().name())));
+    if let Err(err) = result {
+        warn!("⚠️ Could not save game mode to {}: {}", path, err);
+    }
+}
+
+/// `/gamemode <survival|creative|spectator>`
+pub fn switch_game_mode(
+    mut commands: EventReader<ConsoleCommand>,
+    mut next: ResMut<NextState<GameMode>>,
+) {
+    for command in commands.read().filter(|c| c.name == "gamemode") {
+        match command.arg(0).and_then(GameMode::parse) {
+            Some(mode) => {
+                next.set(mode);
+                info!("🎮 Game mode set to {}", mode.name());
+            }
+            None => warn!("⚠️ /gamemode: expected survival, creative or spectator"),
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use bevy::prelude::*;
+
+use crate::player::{
+    body::{Player, WorldSpawn, EYE_HEIGHT},
+    camera::CameraController,
+    movement::{MovementState, PlayerMotion}
+};
+use crate::voxel::{loading::WORLD_BOTTOM, manager::ChunkManager, types::WATER};
+
+// Two points per heart, as in ten hearts of twenty points
+pub const MAX_HEALTH: u32 = 20;
+// Falls shorter than this (in blocks) are free
+const SAFE_FALL_DISTANCE: f32 = 3.0;
+// Seconds of air before drowning starts
+pub const MAX_BREATH: f32 = 15.0;
+const DROWNING_DAMAGE: u32 = 2;
+const DROWNING_INTERVAL: f32 = 1.0;
+// This far below the world bottom the void starts hurting
+const VOID_Y: f32 = WORLD_BOTTOM as f32 - 64.0;
+const VOID_DAMAGE: u32 = 4;
+const VOID_INTERVAL: f32 = 0.5;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DamageCause {
+    Fall,
+    Drowning,
+    Starvation,
+    Void,
+}
+
+/// Sent whenever something hurts a player; health only changes through these
+#[derive(Event)]
+pub struct PlayerDamaged {
+    pub entity: Entity,
+    pub amount: u32,
+    pub cause: DamageCause,
+}
+
+#[derive(Component)]
+pub struct Health {
+    pub current: u32,
+    pub breath: f32,
+    // Highest feet Y since the player last stood on something, None while it can't fall
+    fall_start: Option<f32>,
+    // Counts toward the next drowning or void hit
+    hazard_timer: f32,
+}
+
+impl Default for Health {
+    fn default() -> Self {
+        Self {
+            current: MAX_HEALTH,
+            breath: MAX_BREATH,
+            fall_start: None,
+            hazard_timer: 0.0,
+        }
+    }
+}
+
+impl Health {
+    pub fn is_dead(&self) -> bool {
+        self.current == 0
+    }
+}
+
+/// Run condition for anything a dead player shouldn't be able to do
+pub fn player_alive(player: Query<&Health, With<Player>>) -> bool {
+    player.single().is_ok_and(|h| !h.is_dead())
+}
+
+/// Fall distance is measured from the highest point since leaving the ground;
+/// landing in water or flying cancels it
+pub fn track_fall_damage(
+    player: Query<(Entity, &Transform, &PlayerMotion, &mut Health), With<Player>>,
+    mut damage: EventWriter<PlayerDamaged>,
+) {
+    for (entity, transform, motion, mut health) in player {
+        let y = transform.translation.y;
+        let safe = matches!(motion.state, MovementState::Fly | MovementState::Swim);
+
+        if safe {
+            health.fall_start = None;
+        } else if motion.on_ground {
+            if let Some(start) = health.fall_start.take() {
+                let distance = start - y - SAFE_FALL_DISTANCE;
+                if distance > 0.0 {
+                    damage.write(PlayerDamaged { entity, amount: distance.ceil() as u32, cause: DamageCause::Fall });
+                }
+            }
+        } else {
+            health.fall_start = Some(health.fall_start.map_or(y, |start| start.max(y)));
+        }
+    }
+}
+
+/// Uses up breath while the eyes are under water, then hurts every second; also hurts in the void
+pub fn apply_environment_damage(
+    time: Res<Time>,
+    cm: Res<ChunkManager>,
+    player: Query<(Entity, &Transform, &mut Health), With<Player>>,
+    mut damage: EventWriter<PlayerDamaged>,
+) {
+    let dt = time.delta_secs();
+    for (entity, transform, mut health) in player {
+        let feet = transform.translation;
+        let eye = (feet + Vec3::Y * EYE_HEIGHT).floor().as_ivec3();
+        let submerged = cm.get_block_world(eye).is_some_and(|b| b.id == WATER.id);
+
+        let hazard = if feet.y < VOID_Y {
+            Some((DamageCause::Void, VOID_DAMAGE, VOID_INTERVAL))
+        } else if submerged {
+            health.breath = (health.breath - dt).max(0.0);
+            (health.breath == 0.0).then_some((DamageCause::Drowning, DROWNING_DAMAGE, DROWNING_INTERVAL))
+        } else {
+            None
+        };
+        if !submerged {
+            health.breath = MAX_BREATH;
+        }
+
+        let Some((cause, amount, interval)) = hazard else {
+            health.hazard_timer = 0.0;
+            continue;
+        };
+        health.hazard_timer += dt;
+        if health.hazard_timer >= interval {
+            health.hazard_timer -= interval;
+            damage.write(PlayerDamaged { entity, amount, cause });
+        }
+    }
+}
+
+pub fn apply_damage(
+    mut events: EventReader<PlayerDamaged>,
+    mut health: Query<&mut Health>,
+) {
+    for event in events.read() {
+        let Ok(mut health) = health.get_mut(event.entity) else { continue };
+        if health.is_dead() {
+            continue;
+        }
+
+        health.current = health.current.saturating_sub(event.amount);
+        if health.is_dead() {
+            info!("💀 Player died ({:?})", event.cause);
+        }
+    }
+}
+
+/// Enter on the death screen puts the player back at the world spawn, fully healed
+pub fn respawn_player(
+    keys: Res<ButtonInput<KeyCode>>,
+    spawn: Res<WorldSpawn>,
+    mut player: Query<(&mut Transform, &mut Health, &mut PlayerMotion, &mut CameraController), With<Player>>,
+) {
+    if !keys.just_pressed(KeyCode::Enter) {
+        return;
+    }
+    let Ok((mut transform, mut health, mut motion, mut controller)) = player.single_mut() else { return };
+    if !health.is_dead() {
+        return;
+    }
+
+    transform.translation = spawn.0;
+    *health = Health::default();
+    *motion = PlayerMotion::default();
+    controller.target_pitch = 0.0;
+    info!("❤️ Respawned at {}", spawn.0);
+}
@@ -0,0 +1,168 @@
+use bevy::prelude::*;
+
+use crate::player::{
+    body::Player,
+    gamemode::GameMode,
+    health::{Health, MAX_BREATH, MAX_HEALTH},
+    hunger::Hunger
+};
+
+const FONT_PATH: &str = "fonts/Poppins-Regular.ttf";
+const ICON_SIZE: f32 = 16.0;
+const ICON_GAP: f32 = 2.0;
+const ICON_BORDER: f32 = 2.0;
+const HEART_COLOR: Color = Color::srgb(0.85, 0.1, 0.1);
+const BUBBLE_COLOR: Color = Color::srgb(0.3, 0.6, 1.0);
+const FOOD_COLOR: Color = Color::srgb(0.75, 0.45, 0.15);
+// Between the hearts and the food bar
+const BAR_GAP: f32 = 24.0;
+const EMPTY_COLOR: Color = Color::srgba(0.0, 0.0, 0.0, 0.5);
+
+/// The whole survival HUD, hidden in the other game modes
+#[derive(Component)]
+pub struct SurvivalHud;
+
+#[derive(Component)]
+pub struct BreathRow;
+
+/// The coloured part of one heart, bubble or drumstick, covering `index * 2` to `index * 2 + 2` points
+#[derive(Component)]
+pub enum HudFill {
+    Heart(u32),
+    Bubble(u32),
+    Food(u32),
+}
+
+#[derive(Component)]
+pub struct DeathScreen;
+
+fn icon_row(parent: &mut ChildSpawnerCommands, fill: fn(u32) -> HudFill, color: Color) {
+    for i in 0..MAX_HEALTH / 2 {
+        parent
+            .spawn((
+                Node {
+                    width: Val::Px(ICON_SIZE),
+                    height: Val::Px(ICON_SIZE),
+                    border: UiRect::all(Val::Px(ICON_BORDER)),
+                    ..default()
+                },
+                BorderColor(Color::BLACK),
+                BackgroundColor(EMPTY_COLOR),
+            ))
+            .with_child((
+                Node {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    ..default()
+                },
+                BackgroundColor(color),
+                fill(i),
+            ));
+    }
+}
+
+pub fn setup_hud(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let row = || Node {
+        column_gap: Val::Px(ICON_GAP),
+        ..default()
+    };
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(48.0),
+                width: Val::Percent(100.0),
+                flex_direction: FlexDirection::ColumnReverse,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(ICON_GAP * 2.0),
+                ..default()
+            },
+            Visibility::Hidden,
+            SurvivalHud,
+        ))
+        .with_children(|hud| {
+            hud.spawn(Node { column_gap: Val::Px(BAR_GAP), ..default() }).with_children(|bars| {
+                bars.spawn(row()).with_children(|r| icon_row(r, HudFill::Heart, HEART_COLOR));
+                bars.spawn(row()).with_children(|r| icon_row(r, HudFill::Food, FOOD_COLOR));
+            });
+            hud.spawn((row(), Visibility::Hidden, BreathRow))
+                .with_children(|r| icon_row(r, HudFill::Bubble, BUBBLE_COLOR));
+        });
+
+    let font = asset_server.load(FONT_PATH);
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(16.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.5, 0.0, 0.0, 0.6)),
+            GlobalZIndex(1),
+            Visibility::Hidden,
+            DeathScreen,
+        ))
+        .with_children(|screen| {
+            screen.spawn((
+                Text::new("You died!"),
+                TextFont { font: font.clone(), font_size: 48.0, ..default() },
+                TextColor(Color::WHITE),
+            ));
+            screen.spawn((
+                Text::new("Press Enter to respawn"),
+                TextFont { font, font_size: 20.0, ..default() },
+                TextColor(Color::WHITE),
+            ));
+        });
+}
+
+/// Resizes each fill to full, half or empty; breath only shows while it's running out
+pub fn update_hud(
+    player: Query<(&Health, &Hunger), With<Player>>,
+    mut fills: Query<(&HudFill, &mut Node)>,
+    mut breath_row: Query<&mut Visibility, With<BreathRow>>,
+) {
+    let Ok((health, hunger)) = player.single() else { return };
+    // Breath is shown in the same twenty points as health
+    let breath = (health.breath / MAX_BREATH * MAX_HEALTH as f32).ceil() as u32;
+
+    for (fill, mut node) in &mut fills {
+        let (points, index) = match *fill {
+            HudFill::Heart(i) => (health.current, i),
+            HudFill::Bubble(i) => (breath, i),
+            HudFill::Food(i) => (hunger.food, i),
+        };
+        let filled = points.saturating_sub(index * 2).min(2);
+        node.width = Val::Percent(filled as f32 * 50.0);
+    }
+
+    if let Ok(mut visibility) = breath_row.single_mut() {
+        *visibility = if health.breath < MAX_BREATH { Visibility::Inherited } else { Visibility::Hidden };
+    }
+}
+
+pub fn toggle_survival_hud(
+    mode: Res<State<GameMode>>,
+    mut hud: Query<&mut Visibility, With<SurvivalHud>>,
+) {
+    let show = *mode.get() == GameMode::Survival;
+    for mut visibility in &mut hud {
+        *visibility = if show { Visibility::Inherited } else { Visibility::Hidden };
+    }
+}
+
+pub fn update_death_screen(
+    player: Query<&Health, (With<Player>, Changed<Health>)>,
+    mut screen: Query<&mut Visibility, With<DeathScreen>>,
+) {
+    let Ok(health) = player.single() else { return };
+    for mut visibility in &mut screen {
+        *visibility = if health.is_dead() { Visibility::Inherited } else { Visibility::Hidden };
+    }
+}
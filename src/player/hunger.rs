@@ -0,0 +1,97 @@
+use bevy::{prelude::*, window::PrimaryWindow};
+
+use crate::item::inventory::Inventory;
+use crate::player::{
+    body::Player,
+    health::{DamageCause, Health, PlayerDamaged, MAX_HEALTH},
+    interaction::SelectedSlot,
+    movement::{MovementState, PlayerMotion}
+};
+
+// Same scale as health, two points per drumstick
+pub const MAX_FOOD: u32 = 20;
+// Exhaustion that costs one food point
+const EXHAUSTION_PER_FOOD: f32 = 4.0;
+// Exhaustion per block travelled in each state
+const SPRINT_EXHAUSTION: f32 = 0.1;
+const SWIM_EXHAUSTION: f32 = 0.01;
+// Healing one point costs this much
+const HEAL_EXHAUSTION: f32 = 6.0;
+// At or above this the player slowly heals, at zero they starve
+const REGEN_FOOD: u32 = 18;
+const STARVATION_DAMAGE: u32 = 1;
+// Seconds between healing or starvation ticks
+const HUNGER_INTERVAL: f32 = 4.0;
+
+#[derive(Component)]
+pub struct Hunger {
+    pub food: u32,
+    exhaustion: f32,
+    timer: f32,
+}
+
+impl Default for Hunger {
+    fn default() -> Self {
+        Self { food: MAX_FOOD, exhaustion: 0.0, timer: 0.0 }
+    }
+}
+
+/// Sprinting and swimming tire the player out; a full stomach heals, an empty one hurts
+pub fn update_hunger(
+    time: Res<Time>,
+    player: Query<(Entity, &PlayerMotion, &mut Hunger, &mut Health), With<Player>>,
+    mut damage: EventWriter<PlayerDamaged>,
+) {
+    let dt = time.delta_secs();
+    for (entity, motion, mut hunger, mut health) in player {
+        // The dead come back fed
+        if health.is_dead() {
+            *hunger = Hunger::default();
+            continue;
+        }
+
+        let travelled = motion.velocity.xz().length() * dt;
+        hunger.exhaustion += match motion.state {
+            MovementState::Sprint => travelled * SPRINT_EXHAUSTION,
+            MovementState::Swim => travelled * SWIM_EXHAUSTION,
+            _ => 0.0,
+        };
+        while hunger.exhaustion >= EXHAUSTION_PER_FOOD {
+            hunger.exhaustion -= EXHAUSTION_PER_FOOD;
+            hunger.food = hunger.food.saturating_sub(1);
+        }
+
+        hunger.timer += dt;
+        if hunger.timer < HUNGER_INTERVAL {
+            continue;
+        }
+        hunger.timer -= HUNGER_INTERVAL;
+
+        if hunger.food >= REGEN_FOOD && health.current < MAX_HEALTH {
+            health.current += 1;
+            hunger.exhaustion += HEAL_EXHAUSTION;
+        } else if hunger.food == 0 {
+            damage.write(PlayerDamaged { entity, amount: STARVATION_DAMAGE, cause: DamageCause::Starvation });
+        }
+    }
+}
+
+/// Right click with food in the selected slot eats one of it
+pub fn eat_food(
+    mouse: Res<ButtonInput<MouseButton>>,
+    selected: Res<SelectedSlot>,
+    mut player: Query<(&Health, &mut Hunger, &mut Inventory), With<Player>>,
+    window: Query<&Window, With<PrimaryWindow>>,
+) {
+    if !mouse.just_pressed(MouseButton::Right) || window.single().is_ok_and(|w| w.cursor_options.visible) {
+        return;
+    }
+    let Ok((health, mut hunger, mut inventory)) = player.single_mut() else { return };
+    let Some(food) = inventory.slots[selected.0].map(|s| s.item.food).filter(|&f| f > 0) else { return };
+    if health.is_dead() || hunger.food >= MAX_FOOD {
+        return;
+    }
+
+    inventory.take_one(selected.0);
+    hunger.food = (hunger.food + food).min(MAX_FOOD);
+}
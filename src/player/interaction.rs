@@ -0,0 +1,119 @@
+use bevy::{input::mouse::AccumulatedMouseScroll, prelude::*, window::PrimaryWindow};
+
+use crate::item::inventory::{Inventory, HOTBAR_SLOTS};
+use crate::player::{
+    body::{look_ray, Player},
+    camera::CameraController,
+    gamemode::GameMode,
+    movement::{body_cells, PlayerMotion}
+};
+use crate::voxel::{
+    change::BlockChangeCause,
+    manager::ChunkManager,
+    raycast::raycast,
+    types::{Block, AIR, BLOCKS, CRAFTING_TABLE}
+};
+
+// How far away (in blocks) the player can break and place
+pub const REACH: f32 = 6.0;
+
+const HOTBAR_KEYS: [KeyCode; HOTBAR_SLOTS] = [
+    KeyCode::Digit1, KeyCode::Digit2, KeyCode::Digit3,
+    KeyCode::Digit4, KeyCode::Digit5, KeyCode::Digit6,
+    KeyCode::Digit7, KeyCode::Digit8, KeyCode::Digit9,
+];
+
+/// Hotbar slot in use: the held item in survival, a palette block otherwise
+#[derive(Resource, Default)]
+pub struct SelectedSlot(pub usize);
+
+/// How far the creative hotbar is scrolled through the palette
+#[derive(Resource, Default)]
+pub struct PaletteOffset(pub usize);
+
+// Creative and spectator build from every placeable block, in `BLOCKS` order,
+// with slot n holding palette block `offset + n` and wrapping round at the end
+fn palette_block(offset: usize, slot: usize) -> Option<Block> {
+    let palette = || BLOCKS.iter().copied().filter(|b| b.is_solid());
+    palette().nth((offset + slot) % palette().count())
+}
+
+/// Number keys pick a hotbar slot; outside survival the mouse wheel scrolls the palette
+pub fn select_slot(
+    keys: Res<ButtonInput<KeyCode>>,
+    scroll: Res<AccumulatedMouseScroll>,
+    mode: Res<State<GameMode>>,
+    mut selected: ResMut<SelectedSlot>,
+    mut offset: ResMut<PaletteOffset>,
+    inventory: Query<&Inventory, With<Player>>,
+) {
+    let survival = *mode.get() == GameMode::Survival;
+    if let Some(slot) = HOTBAR_KEYS.iter().position(|key| keys.just_pressed(*key)) {
+        selected.0 = slot;
+    } else if !survival && scroll.delta.y != 0.0 {
+        let len = BLOCKS.iter().filter(|b| b.is_solid()).count();
+        offset.0 = if scroll.delta.y < 0.0 { (offset.0 + 1) % len } else { (offset.0 + len - 1) % len };
+    } else {
+        return;
+    }
+
+    let name = if survival {
+        inventory.single().ok().and_then(|i| i.slots[selected.0]).map(|s| s.item.name)
+    } else {
+        palette_block(offset.0, selected.0).map(|b| b.name)
+    };
+    info!("🧱 Selected {}", name.unwrap_or("nothing"));
+}
+
+/// Left click breaks the targeted block outside survival, where it's mined instead;
+/// right click places the selected block against it, using up the held item in survival
+pub fn break_and_place_blocks(
+    mouse: Res<ButtonInput<MouseButton>>,
+    mode: Res<State<GameMode>>,
+    selected: Res<SelectedSlot>,
+    offset: Res<PaletteOffset>,
+    mut cm: ResMut<ChunkManager>,
+    mut player: Query<(&Transform, &CameraController, &PlayerMotion, &mut Inventory), With<Player>>,
+    window: Query<&Window, With<PrimaryWindow>>,
+) {
+    // A visible cursor means the player is clicking on something else
+    if window.single().is_ok_and(|w| w.cursor_options.visible) {
+        return;
+    }
+    let Ok((body, controller, motion, mut inventory)) = player.single_mut() else { return };
+
+    let survival = *mode.get() == GameMode::Survival;
+    let breaking = mouse.just_pressed(MouseButton::Left) && !survival;
+    let placing = mouse.just_pressed(MouseButton::Right);
+    if !breaking && !placing {
+        return;
+    }
+
+    let (eye, look) = look_ray(body, controller);
+    let Some(hit) = raycast(&cm, eye, look, REACH) else { return };
+
+    if breaking {
+        cm.set_block_world(hit.pos, AIR, BlockChangeCause::Player);
+    } else if hit.normal != IVec3::ZERO && cm.get_block_world(hit.pos).is_some_and(|b| b.id != CRAFTING_TABLE.id) {
+        // Right clicking a crafting table opens it instead
+        let target = hit.pos + hit.normal;
+        let occupied = cm.get_block_world(target).is_none_or(|b| b.is_solid());
+        // Don't place blocks inside the player's own body
+        let (min, max) = body_cells(body.translation, motion.body_height());
+        if occupied || (target.cmpge(min) & target.cmple(max)).all() {
+            return;
+        }
+
+        let block = if survival {
+            inventory.slots[selected.0].and_then(|s| s.item.block)
+        } else {
+            palette_block(offset.0, selected.0)
+        };
+        if let Some(block) = block {
+            cm.set_block_world(target, block, BlockChangeCause::Player);
+            if survival {
+                inventory.take_one(selected.0);
+            }
+        }
+    }
+}
@@ -0,0 +1,204 @@
+use bevy::{
+    asset::RenderAssetUsages,
+    image::ImageSampler,
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+    window::PrimaryWindow,
+};
+
+use crate::item::inventory::Inventory;
+use crate::player::{
+    body::{look_ray, Player},
+    camera::CameraController,
+    health::Health,
+    interaction::{SelectedSlot, REACH}
+};
+use crate::voxel::{
+    change::BlockChangeCause,
+    manager::ChunkManager,
+    random::WorldRng,
+    raycast::raycast,
+    types::AIR
+};
+
+const CRACK_STAGES: usize = 10;
+const CRACK_SIZE: u32 = 16;
+const CRACK_BRANCHES: usize = 6;
+const CRACK_SEED: u64 = 0xC4AC_0000_0000_0001;
+// Lifts the overlay off the face so it doesn't z-fight with the chunk mesh
+const OVERLAY_OFFSET: f32 = 0.002;
+
+/// The block being mined and how far along it is, from 0 to 1
+#[derive(Resource, Default)]
+pub struct Mining {
+    target: Option<IVec3>,
+    face: IVec3,
+    block_id: u8,
+    progress: f32,
+}
+
+impl Mining {
+    fn cancel(&mut self) {
+        self.target = None;
+        self.progress = 0.0;
+    }
+
+    /// Which crack texture to show, None before any progress has been made
+    pub fn stage(&self) -> Option<usize> {
+        if self.target.is_none() || self.progress <= 0.0 {
+            return None;
+        }
+        Some(((self.progress * CRACK_STAGES as f32) as usize).min(CRACK_STAGES - 1))
+    }
+}
+
+/// One material per crack stage, shared by the single overlay quad
+#[derive(Resource)]
+pub struct CrackMaterials(Vec<Handle<StandardMaterial>>);
+
+#[derive(Component)]
+pub struct CrackOverlay;
+
+// Random walks out from the middle, in the order they're drawn, so each
+// stage shows the previous one's cracks plus some more
+fn crack_pixels() -> Vec<UVec2> {
+    let mut rng = WorldRng::new(CRACK_SEED);
+    let mut pixels = Vec::new();
+    let centre = IVec2::splat(CRACK_SIZE as i32 / 2);
+    let mut heads = vec![centre; CRACK_BRANCHES];
+
+    while pixels.len() < (CRACK_SIZE * CRACK_SIZE / 3) as usize {
+        for head in &mut heads {
+            *head += IVec2::new(rng.range_i32(-1, 1), rng.range_i32(-1, 1));
+            *head = head.clamp(IVec2::ZERO, IVec2::splat(CRACK_SIZE as i32 - 1));
+            let pixel = head.as_uvec2();
+            if !pixels.contains(&pixel) {
+                pixels.push(pixel);
+            }
+        }
+    }
+    pixels
+}
+
+fn crack_image(pixels: &[UVec2]) -> Image {
+    let mut data = vec![0; (CRACK_SIZE * CRACK_SIZE * 4) as usize];
+    for pixel in pixels {
+        let i = ((pixel.y * CRACK_SIZE + pixel.x) * 4) as usize;
+        data[i..i + 4].copy_from_slice(&[20, 20, 20, 200]);
+    }
+
+    let mut image = Image::new(
+        Extent3d { width: CRACK_SIZE, height: CRACK_SIZE, depth_or_array_layers: 1 },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::RENDER_WORLD,
+    );
+    image.sampler = ImageSampler::nearest();
+    image
+}
+
+pub fn setup_crack_overlay(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    let pixels = crack_pixels();
+    let stages = (1..=CRACK_STAGES)
+        .map(|stage| {
+            let image = crack_image(&pixels[..pixels.len() * stage / CRACK_STAGES]);
+            materials.add(StandardMaterial {
+                base_color_texture: Some(images.add(image)),
+                alpha_mode: AlphaMode::Blend,
+                unlit: true,
+                ..default()
+            })
+        })
+        .collect::<Vec<_>>();
+
+    commands.spawn((
+        Mesh3d(meshes.add(Rectangle::new(1.0, 1.0))),
+        MeshMaterial3d(stages[0].clone()),
+        Transform::default(),
+        Visibility::Hidden,
+        CrackOverlay,
+    ));
+    commands.insert_resource(CrackMaterials(stages));
+}
+
+/// Holding left click on the same block builds up progress until it breaks, faster with
+/// the right tool in hand; looking away, letting go or the block changing starts over
+pub fn mine_blocks(
+    mouse: Res<ButtonInput<MouseButton>>,
+    time: Res<Time>,
+    selected: Res<SelectedSlot>,
+    mut mining: ResMut<Mining>,
+    mut cm: ResMut<ChunkManager>,
+    mut player: Query<(&Transform, &CameraController, &Health, &mut Inventory), With<Player>>,
+    window: Query<&Window, With<PrimaryWindow>>,
+) {
+    let Ok((body, controller, health, mut inventory)) = player.single_mut() else { return };
+    let released = window.single().is_ok_and(|w| w.cursor_options.visible);
+    if released || health.is_dead() || !mouse.pressed(MouseButton::Left) {
+        mining.cancel();
+        return;
+    }
+
+    let (eye, look) = look_ray(body, controller);
+    let Some(hit) = raycast(&cm, eye, look, REACH) else {
+        mining.cancel();
+        return;
+    };
+    let Some(block) = cm.get_block_world(hit.pos) else { return };
+    if block.hardness < 0.0 {
+        mining.cancel();
+        return;
+    }
+
+    if mining.target != Some(hit.pos) || mining.block_id != block.id {
+        mining.target = Some(hit.pos);
+        mining.block_id = block.id;
+        mining.progress = 0.0;
+    }
+    mining.face = hit.normal;
+    let held = inventory.slots[selected.0].map(|s| s.item);
+    let speed = held.and_then(|i| i.tool).map_or(1.0, |t| t.speed_on(block));
+    mining.progress += if block.hardness == 0.0 { 1.0 } else { time.delta_secs() * speed / block.hardness };
+
+    if mining.progress >= 1.0 {
+        cm.set_block_world(hit.pos, AIR, BlockChangeCause::Player);
+        mining.cancel();
+        if let Some(item) = held
+            && inventory.wear_tool(selected.0)
+        {
+            info!("🔨 {} broke", item.name);
+        }
+    }
+}
+
+pub fn cancel_mining(mut mining: ResMut<Mining>) {
+    mining.cancel();
+}
+
+/// Puts the crack quad on the mined face with the texture for its progress
+pub fn update_crack_overlay(
+    mining: Res<Mining>,
+    cracks: Res<CrackMaterials>,
+    mut overlay: Query<(&mut Transform, &mut MeshMaterial3d<StandardMaterial>, &mut Visibility), With<CrackOverlay>>,
+) {
+    let Ok((mut transform, mut material, mut visibility)) = overlay.single_mut() else { return };
+
+    // Standing inside the block gives no face to draw on
+    let shown = mining.stage().zip(mining.target).filter(|_| mining.face != IVec3::ZERO);
+    let Some((stage, target)) = shown else {
+        *visibility = Visibility::Hidden;
+        return;
+    };
+
+    let normal = mining.face.as_vec3();
+    transform.translation = target.as_vec3() + Vec3::splat(0.5) + normal * (0.5 + OVERLAY_OFFSET);
+    transform.rotation = Quat::from_rotation_arc(Vec3::Z, normal);
+    material.0 = cracks.0[stage].clone();
+    *visibility = Visibility::Inherited;
+}
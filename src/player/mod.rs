@@ -1,4 +1,12 @@
+pub mod avatar;
+pub mod body;
 pub mod camera;
+pub mod gamemode;
+pub mod health;
+pub mod hud;
+pub mod hunger;
+pub mod interaction;
+pub mod mining;
 pub mod movement;
 
 pub use plugin::PlayerPlugin;
@@ -1,66 +1,336 @@
 use bevy::prelude::*;
 use bevy::input::mouse::MouseMotion;
+use bevy::window::PrimaryWindow;
 
-use crate::player::camera::PlayerCamera;
+use crate::console::ui::ConsoleState;
+use crate::player::{
+    body::Player,
+    camera::{CameraController, CameraMode, CameraRig},
+    gamemode::GameMode
+};
+use crate::voxel::{loading::WORLD_BOTTOM, manager::ChunkManager, types::WATER};
 
-const SPEED: f32 = 10.0;
-const SENSITIVITY: f32 = 0.0005;
+pub const SENSITIVITY: f32 = 0.0005;
 
+// Blocks per second for each state
+const WALK_SPEED: f32 = 4.3;
+const SPRINT_SPEED: f32 = 5.6;
+const CROUCH_SPEED: f32 = 1.3;
+const SWIM_SPEED: f32 = 2.2;
+const FLY_SPEED: f32 = 10.8;
+const FLY_SPRINT_MULTIPLIER: f32 = 2.0;
+
+// How quickly velocity closes in on the input velocity, per second.
+// Low values feel slippery, high values close to instant
+const GROUND_CONTROL: f32 = 14.0;
+const AIR_CONTROL: f32 = 2.5;
+const SWIM_CONTROL: f32 = 5.0;
+const FLY_CONTROL: f32 = 6.0;
+
+const GRAVITY: f32 = 28.0;
+const TERMINAL_VELOCITY: f32 = 60.0;
+const JUMP_VELOCITY: f32 = 8.5;
+// Water pulls down gently, holding Space pushes up harder, and drag bleeds off vertical speed
+const WATER_GRAVITY: f32 = 6.0;
+const SWIM_BUOYANCY: f32 = 16.0;
+const WATER_DRAG: f32 = 2.5;
+
+// Seconds between two presses that count as a double tap
+const DOUBLE_TAP_WINDOW: f32 = 0.3;
+
+pub const PLAYER_HALF_WIDTH: f32 = 0.3;
+pub const PLAYER_HEIGHT: f32 = 1.8;
+const CROUCH_HEIGHT: f32 = 1.5;
+// Movement is split into steps no longer than this so fast falls can't tunnel through floors
+const MAX_STEP: f32 = 0.45;
+// Kept between the body and the block it was stopped by
+const SKIN_WIDTH: f32 = 0.001;
+
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum MovementState {
+    #[default]
+    Walk,
+    Sprint,
+    Crouch,
+    Swim,
+    Fly,
+}
+
+#[derive(Component)]
+pub struct PlayerMotion {
+    pub velocity: Vec3,
+    pub on_ground: bool,
+    pub state: MovementState,
+    // Toggled by double-tapping Space in creative, always on in spectator
+    pub flying: bool,
+    // Double-tapping W sprints until W is released
+    sprint_latched: bool,
+    last_forward_tap: f32,
+    last_jump_tap: f32,
+}
+
+impl Default for PlayerMotion {
+    fn default() -> Self {
+        Self {
+            velocity: Vec3::ZERO,
+            on_ground: false,
+            state: MovementState::Walk,
+            flying: false,
+            sprint_latched: false,
+            last_forward_tap: f32::NEG_INFINITY,
+            last_jump_tap: f32::NEG_INFINITY,
+        }
+    }
+}
+
+impl PlayerMotion {
+    /// Height of the body box, which shrinks while crouching
+    pub fn body_height(&self) -> f32 {
+        if self.state == MovementState::Crouch { CROUCH_HEIGHT } else { PLAYER_HEIGHT }
+    }
+}
+
+// Unloaded chunks count as solid, so the player can't fall out of the loaded world,
+// but nothing is ever loaded below the world bottom so that stays open to the void
+fn blocked(cm: &ChunkManager, cell: IVec3) -> bool {
+    cell.y >= WORLD_BOTTOM && cm.get_block_world(cell).is_none_or(|b| b.is_solid())
+}
+
+/// True when the body box standing at `feet` overlaps anything solid
+/// Inclusive range of block cells the body box standing at `feet` overlaps
+pub fn body_cells(feet: Vec3, height: f32) -> (IVec3, IVec3) {
+    let half = Vec3::new(PLAYER_HALF_WIDTH, 0.0, PLAYER_HALF_WIDTH);
+    let min = (feet - half).floor().as_ivec3();
+    let max = (feet + half + Vec3::Y * height - Vec3::splat(SKIN_WIDTH)).floor().as_ivec3();
+    (min, max)
+}
+
+fn collides(cm: &ChunkManager, feet: Vec3, height: f32) -> bool {
+    let (min, max) = body_cells(feet, height);
+
+    (min.y..=max.y).any(|y| {
+        (min.z..=max.z).any(|z| (min.x..=max.x).any(|x| blocked(cm, IVec3::new(x, y, z))))
+    })
+}
+
+/// True when some block under the footprint at `feet` could be stood on
+fn supported(cm: &ChunkManager, feet: Vec3) -> bool {
+    let below = feet - Vec3::Y * 0.05;
+    let min = (below - Vec3::new(PLAYER_HALF_WIDTH, 0.0, PLAYER_HALF_WIDTH)).floor().as_ivec3();
+    let max = (below + Vec3::new(PLAYER_HALF_WIDTH, 0.0, PLAYER_HALF_WIDTH)).floor().as_ivec3();
+
+    (min.z..=max.z).any(|z| (min.x..=max.x).any(|x| blocked(cm, IVec3::new(x, min.y, z))))
+}
+
+fn in_water(cm: &ChunkManager, feet: Vec3) -> bool {
+    // Checked a little above the feet so wading through a puddle doesn't count
+    let cell = (feet + Vec3::Y * 0.4).floor().as_ivec3();
+    cm.get_block_world(cell).is_some_and(|b| b.id == WATER.id)
+}
+
+/// Moves along one axis, stopping flush against whatever is in the way
+fn move_axis(cm: &ChunkManager, feet: &mut Vec3, axis: usize, delta: f32, height: f32) -> bool {
+    let mut next = *feet;
+    next[axis] += delta;
+    if !collides(cm, next, height) {
+        *feet = next;
+        return false;
+    }
+
+    // Snap to the face of the blocking cell
+    let extent = if axis == 1 {
+        if delta > 0.0 { height } else { 0.0 }
+    } else {
+        PLAYER_HALF_WIDTH * delta.signum()
+    };
+    let edge = next[axis] + extent;
+    let face = if delta > 0.0 { edge.floor() } else { edge.floor() + 1.0 };
+    next[axis] = face - extent - SKIN_WIDTH * delta.signum();
+    if !collides(cm, next, height) && (next[axis] - feet[axis]) * delta >= 0.0 {
+        *feet = next;
+    }
+    true
+}
+
+/// Walk, sprint (Left Ctrl or double-tap W), crouch (Shift, won't step off edges), swim and fly
+/// (double-tap Space in creative, Shift to descend). Spectators fly through blocks.
+/// Velocity eases toward the input instead of jumping to it
 pub fn player_movement(
     keyboard: Res<ButtonInput<KeyCode>>,
     time: Res<Time>,
-    mut query: Query<&mut Transform, With<PlayerCamera>>,
+    console: Res<ConsoleState>,
+    mode: Res<State<GameMode>>,
+    cm: Res<ChunkManager>,
+    mut query: Query<(&mut Transform, &mut CameraController, &mut PlayerMotion), With<Player>>,
 ) {
-    if let Ok(mut transform) = query.single_mut() {
-        let forward = transform.forward().normalize();
-        let right = transform.right().normalize();
+    let Ok((mut transform, mut controller, mut motion)) = query.single_mut() else { return };
+    // Long frames are capped so a hitch can't launch the player through terrain
+    let dt = time.delta_secs().min(0.05);
+    let now = time.elapsed_secs();
 
-        let mut direction = Vec3::ZERO;
+    // Gravity keeps running while typing, only input is dropped
+    let pressed = |key: KeyCode| !console.open && keyboard.pressed(key);
+    let just_pressed = |key: KeyCode| !console.open && keyboard.just_pressed(key);
 
-        if keyboard.pressed(KeyCode::KeyW) {
-            direction += forward;
-        }
-        if keyboard.pressed(KeyCode::KeyS) {
-            direction -= forward;
+    let spectator = *mode.get() == GameMode::Spectator;
+    match mode.get() {
+        GameMode::Survival if motion.flying => motion.flying = false,
+        GameMode::Spectator => motion.flying = true,
+        _ => {}
+    }
+
+    if just_pressed(KeyCode::Space) && *mode.get() == GameMode::Creative {
+        if now - motion.last_jump_tap < DOUBLE_TAP_WINDOW {
+            motion.flying = !motion.flying;
+            motion.velocity.y = 0.0;
+            motion.last_jump_tap = f32::NEG_INFINITY;
+            info!("🕊️ Flying {}", if motion.flying { "on" } else { "off" });
+        } else {
+            motion.last_jump_tap = now;
         }
-        if keyboard.pressed(KeyCode::KeyA) {
-            direction -= right;
+    }
+    if just_pressed(KeyCode::KeyW) {
+        motion.sprint_latched = now - motion.last_forward_tap < DOUBLE_TAP_WINDOW;
+        motion.last_forward_tap = now;
+    }
+    if !pressed(KeyCode::KeyW) {
+        motion.sprint_latched = false;
+    }
+
+    let swimming = in_water(&cm, transform.translation);
+    let sprint_key = pressed(KeyCode::ControlLeft) || motion.sprint_latched;
+    let headroom = !collides(&cm, transform.translation, PLAYER_HEIGHT);
+    motion.state = if motion.flying {
+        MovementState::Fly
+    } else if swimming {
+        MovementState::Swim
+    } else if pressed(KeyCode::ShiftLeft) || (motion.state == MovementState::Crouch && !headroom) {
+        // Stays crouched under low ceilings until there's room to stand up
+        MovementState::Crouch
+    } else if sprint_key && pressed(KeyCode::KeyW) {
+        MovementState::Sprint
+    } else {
+        MovementState::Walk
+    };
+
+    // Walking follows the body's heading, so looking down doesn't slow the player
+    let heading = Quat::from_rotation_y(controller.yaw);
+    let mut input = Vec3::ZERO;
+    if pressed(KeyCode::KeyW) { input += heading * Vec3::NEG_Z; }
+    if pressed(KeyCode::KeyS) { input -= heading * Vec3::NEG_Z; }
+    if pressed(KeyCode::KeyD) { input += heading * Vec3::X; }
+    if pressed(KeyCode::KeyA) { input -= heading * Vec3::X; }
+
+    let speed = match motion.state {
+        MovementState::Walk => WALK_SPEED,
+        MovementState::Sprint => SPRINT_SPEED,
+        MovementState::Crouch => CROUCH_SPEED,
+        MovementState::Swim => SWIM_SPEED,
+        MovementState::Fly if sprint_key => FLY_SPEED * FLY_SPRINT_MULTIPLIER,
+        MovementState::Fly => FLY_SPEED,
+    };
+    let wish = input.normalize_or_zero() * speed;
+
+    let control = match motion.state {
+        MovementState::Fly => FLY_CONTROL,
+        MovementState::Swim => SWIM_CONTROL,
+        _ if motion.on_ground => GROUND_CONTROL,
+        _ => AIR_CONTROL,
+    };
+    let t = 1.0 - (-control * dt).exp();
+    motion.velocity.x += (wish.x - motion.velocity.x) * t;
+    motion.velocity.z += (wish.z - motion.velocity.z) * t;
+
+    match motion.state {
+        MovementState::Fly => {
+            let vertical = (pressed(KeyCode::Space) as i32 - pressed(KeyCode::ShiftLeft) as i32) as f32;
+            motion.velocity.y += (vertical * speed - motion.velocity.y) * t;
         }
-        if keyboard.pressed(KeyCode::KeyD) {
-            direction += right;
+        MovementState::Swim => {
+            motion.velocity.y -= WATER_GRAVITY * dt;
+            if pressed(KeyCode::Space) {
+                motion.velocity.y += SWIM_BUOYANCY * dt;
+            }
+            motion.velocity.y *= (-WATER_DRAG * dt).exp();
         }
-        if keyboard.pressed(KeyCode::ControlLeft) {
-            direction -= Vec3::Y;
+        _ => {
+            motion.velocity.y = (motion.velocity.y - GRAVITY * dt).max(-TERMINAL_VELOCITY);
+            if motion.on_ground && pressed(KeyCode::Space) {
+                motion.velocity.y = JUMP_VELOCITY;
+            }
         }
-        if keyboard.pressed(KeyCode::Space) {
-            direction += Vec3::Y;
+    }
+
+    controller.sprinting = wish != Vec3::ZERO
+        && (motion.state == MovementState::Sprint || (motion.state == MovementState::Fly && sprint_key));
+
+    if spectator {
+        transform.translation += motion.velocity * dt;
+        motion.on_ground = false;
+        return;
+    }
+
+    let height = motion.body_height();
+    let delta = motion.velocity * dt;
+    let steps = (delta.abs().max_element() / MAX_STEP).ceil().max(1.0);
+    let step = delta / steps;
+    let mut feet = transform.translation;
+    let mut on_ground = false;
+
+    for _ in 0..steps as u32 {
+        // Vertical first, so edge-sneak checks support at the final height
+        if move_axis(&cm, &mut feet, 1, step.y, height) {
+            on_ground |= step.y < 0.0;
+            motion.velocity.y = 0.0;
         }
 
-        if direction.length_squared() > 0.0 {
-            transform.translation += direction.normalize() * SPEED * time.delta_secs();
+        for axis in [0, 2] {
+            let before = feet;
+            if move_axis(&cm, &mut feet, axis, step[axis], height) {
+                motion.velocity[axis] = 0.0;
+            }
+            // Crouching never steps off an edge
+            if motion.state == MovementState::Crouch && motion.on_ground && !supported(&cm, feet) {
+                feet = before;
+                motion.velocity[axis] = 0.0;
+            }
         }
     }
+
+    motion.on_ground = on_ground || (motion.velocity.y <= 0.0 && supported(&cm, feet));
+    // Descending onto the ground ends flight
+    if motion.flying && motion.on_ground && motion.velocity.y <= 0.0 && pressed(KeyCode::ShiftLeft) {
+        motion.flying = false;
+    }
+    transform.translation = feet;
 }
 
 pub fn mouse_look(
+    time: Res<Time>,
+    console: Res<ConsoleState>,
     mut mouse_events: EventReader<MouseMotion>,
-    mut query: Query<&mut Transform, With<PlayerCamera>>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    mut player: Query<(&mut Transform, &mut CameraController), With<Player>>,
+    mut rig: Query<&mut CameraRig>,
 ) {
-    if let Ok(mut transform) = query.single_mut() {
-        let mut delta = Vec2::ZERO;
-        for event in mouse_events.read() {
-            delta += event.delta;
-        }
+    let mut delta = Vec2::ZERO;
+    for event in mouse_events.read() {
+        delta += event.delta;
+    }
 
-        if delta.length_squared() > 0.0 {
-            let yaw = Quat::from_rotation_y(-delta.x * SENSITIVITY);
-            let pitch = Quat::from_rotation_x(-delta.y * SENSITIVITY);
+    // The mouse belongs to the UI while the cursor is released with Escape or the console is open
+    let released = console.open || window.single().is_ok_and(|w| w.cursor_options.visible);
 
-            // apply yaw (global Y axis)
-            transform.rotation = yaw * transform.rotation;
+    let Ok((mut transform, mut controller)) = player.single_mut() else { return };
+    let orbiting = rig.single().is_ok_and(|r| r.mode == CameraMode::Orbit);
 
-            // apply pitch (local X axis)
-            transform.rotation *= pitch;
+    if !released {
+        match rig.single_mut() {
+            Ok(mut rig) if orbiting => rig.add_orbit_look(delta, controller.sensitivity),
+            _ => controller.add_look(delta),
         }
     }
+    controller.update(time.delta_secs());
+    // The body only turns around Y, the head carries the pitch
+    transform.rotation = Quat::from_rotation_y(controller.yaw);
 }
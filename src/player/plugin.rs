@@ -1,7 +1,17 @@
 use bevy::prelude::*;
 
+use crate::console::ui::console_closed;
+use crate::worldedit::selection::wand_inactive;
 use crate::player::{
-    camera::spawn_player_camera,
+    avatar::{animate_avatars, change_skin, spawn_player_avatar, sync_local_avatar},
+    body::spawn_player,
+    camera::{cycle_camera_mode, set_look_smoothing, update_camera_rig, update_sprint_fov},
+    gamemode::{load_game_mode, save_game_mode, switch_game_mode, GameMode, PlayerProfile},
+    health::{apply_damage, apply_environment_damage, player_alive, respawn_player, track_fall_damage, PlayerDamaged},
+    hud::{setup_hud, toggle_survival_hud, update_death_screen, update_hud},
+    hunger::{eat_food, update_hunger},
+    interaction::{break_and_place_blocks, select_slot, PaletteOffset, SelectedSlot},
+    mining::{cancel_mining, mine_blocks, setup_crack_overlay, update_crack_overlay, Mining},
     movement::{player_movement, mouse_look}
 };
 
@@ -10,7 +20,43 @@ pub struct PlayerPlugin;
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
         app
-            .add_systems(Startup, spawn_player_camera)
-            .add_systems(Update, (player_movement, mouse_look));
+            .init_resource::<SelectedSlot>()
+            .init_resource::<PaletteOffset>()
+            .init_resource::<Mining>()
+            .init_resource::<PlayerProfile>()
+            .init_state::<GameMode>()
+            .add_event::<PlayerDamaged>()
+            .add_systems(Startup, (load_game_mode, spawn_player, spawn_player_avatar, setup_hud, setup_crack_overlay).chain())
+            .add_systems(OnExit(GameMode::Survival), cancel_mining)
+            .add_systems(Update, (
+                (
+                    player_movement.run_if(player_alive),
+                    cycle_camera_mode.run_if(console_closed),
+                    mouse_look,
+                    update_camera_rig
+                ).chain(),
+                select_slot.run_if(console_closed),
+                update_sprint_fov,
+                change_skin,
+                set_look_smoothing,
+                (sync_local_avatar, animate_avatars).chain(),
+                break_and_place_blocks.run_if(
+                    wand_inactive.and(player_alive).and(not(in_state(GameMode::Spectator)))
+                ),
+                (
+                    (track_fall_damage, apply_environment_damage, update_hunger).run_if(in_state(GameMode::Survival)),
+                    apply_damage,
+                    (update_hud, update_death_screen)
+                ).chain().after(player_movement),
+                (
+                    mine_blocks.run_if(in_state(GameMode::Survival).and(wand_inactive)),
+                    update_crack_overlay
+                ).chain(),
+                eat_food.run_if(in_state(GameMode::Survival)),
+                respawn_player.run_if(console_closed),
+                toggle_survival_hud.run_if(state_changed::<GameMode>),
+                switch_game_mode,
+                save_game_mode.run_if(state_changed::<GameMode>)
+            ));
     }
 }
@@ -13,7 +13,6 @@ use bevy::{
     winit::WinitWindows,
 };
 
-use image;
 use winit::window::Icon;
 
 pub struct SetupPlugin;
@@ -87,16 +86,16 @@ fn toggle_cursor(
     mut windows: Query<&mut Window>,
     keys: Res<ButtonInput<KeyCode>>,
 ) {
-    if keys.just_pressed(KeyCode::Escape) {
-        if let Ok(mut window) = windows.single_mut() {
-            // Toggle cursor grab mode
-            if window.cursor_options.visible {
-                window.cursor_options.visible = false;
-                window.cursor_options.grab_mode = bevy::window::CursorGrabMode::Confined;
-            } else {
-                window.cursor_options.visible = true;
-                window.cursor_options.grab_mode = bevy::window::CursorGrabMode::None;
-            }
+    if keys.just_pressed(KeyCode::Escape)
+        && let Ok(mut window) = windows.single_mut()
+    {
+        // Toggle cursor grab mode
+        if window.cursor_options.visible {
+            window.cursor_options.visible = false;
+            window.cursor_options.grab_mode = bevy::window::CursorGrabMode::Confined;
+        } else {
+            window.cursor_options.visible = true;
+            window.cursor_options.grab_mode = bevy::window::CursorGrabMode::None;
         }
     }
 }
\ No newline at end of file
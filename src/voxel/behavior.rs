@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+use crate::voxel::{
+    tick::TickContext,
+    types::{Block, AIR, DIRT, GRASS, LEAVES, SAND, WOOD}
+};
+
+// Leaves further than this (in blocks) from any wood will decay
+const LEAF_DECAY_RADIUS: i32 = 4;
+// Attempts per random tick for grass to spread onto nearby dirt
+const GRASS_SPREAD_ATTEMPTS: usize = 4;
+const GRAVITY_CHECK_DELAY: u64 = 2;
+
+/// Per-block simulation hook called by the world tick
+pub trait BlockBehavior: Send + Sync {
+    /// Called when the random tick pass happens to pick this block
+    fn random_tick(&self, _ctx: &mut TickContext, _pos: IVec3, _block: Block) {}
+
+    /// Called when a scheduled update for this position comes due
+    fn scheduled_tick(&self, _ctx: &mut TickContext, _pos: IVec3, _block: Block) {}
+}
+
+/// Registry of behaviours keyed by block id
+#[derive(Resource, Default)]
+pub struct BlockBehaviors {
+    behaviors: HashMap<u8, Box<dyn BlockBehavior>>,
+}
+
+impl BlockBehaviors {
+    pub fn register(&mut self, block: Block, behavior: impl BlockBehavior + 'static) {
+        self.behaviors.insert(block.id, Box::new(behavior));
+    }
+
+    pub fn get(&self, block: Block) -> Option<&dyn BlockBehavior> {
+        self.behaviors.get(&block.id).map(|b| b.as_ref())
+    }
+
+    pub fn with_defaults() -> Self {
+        let mut behaviors = Self::default();
+        behaviors.register(GRASS, GrassBehavior);
+        behaviors.register(LEAVES, LeavesBehavior);
+        behaviors.register(SAND, GravityBehavior);
+        behaviors
+    }
+}
+
+pub struct GrassBehavior;
+
+impl BlockBehavior for GrassBehavior {
+    fn random_tick(&self, ctx: &mut TickContext, pos: IVec3, _block: Block) {
+        // Grass smothered by a block above turns back into dirt; an unloaded chunk
+        // above says nothing about that, so wait for it
+        let Some(above) = ctx.get_block(pos + IVec3::Y) else { return };
+        if above.id != AIR.id {
+            ctx.set_block(pos, DIRT);
+            return;
+        }
+
+        for _ in 0..GRASS_SPREAD_ATTEMPTS {
+            let target = pos + IVec3::new(
+                ctx.rng.range_i32(-1, 1),
+                ctx.rng.range_i32(-3, 1),
+                ctx.rng.range_i32(-1, 1),
+            );
+
+            // Only dirt that can see the sky turns green, so caves stay bare
+            let is_dirt = matches!(ctx.get_block(target), Some(b) if b.id == DIRT.id);
+            if is_dirt && ctx.world.is_sky_exposed(target) {
+                ctx.set_block(target, GRASS);
+            }
+        }
+    }
+}
+
+pub struct LeavesBehavior;
+
+impl BlockBehavior for LeavesBehavior {
+    fn random_tick(&self, ctx: &mut TickContext, pos: IVec3, _block: Block) {
+        let r = IVec3::splat(LEAF_DECAY_RADIUS);
+        let supported = ctx.world.blocks_in_aabb(pos - r, pos + r)
+            .any(|(_, block)| matches!(block, Some(b) if b.id == WOOD.id));
+
+        if !supported {
+            ctx.set_block(pos, AIR);
+        }
+    }
+}
+
+// Shared by every block flagged with `Block::gravity`
+pub struct GravityBehavior;
+
+impl BlockBehavior for GravityBehavior {
+    fn random_tick(&self, ctx: &mut TickContext, pos: IVec3, _block: Block) {
+        // Catches blocks that were generated floating and never received an update
+        ctx.schedule(pos, GRAVITY_CHECK_DELAY);
+    }
+
+    fn scheduled_tick(&self, ctx: &mut TickContext, pos: IVec3, block: Block) {
+        let supported = ctx.get_block(pos - IVec3::Y).is_none_or(|b| b.is_solid());
+        if block.gravity && !supported {
+            ctx.detach_block(pos, block);
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use bevy::prelude::*;
+
+use crate::voxel::{manager::ChunkManager, types::Block};
+
+/// What made a block change
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockChangeCause {
+    // Random and scheduled block behaviours
+    Tick,
+    // A falling block landing
+    Physics,
+    Player,
+    // Bulk building tools
+    Edit,
+    Undo,
+    Redo,
+}
+
+/// Sent for every block write that changed a loaded block; generation is not reported
+#[derive(Event, Debug, Clone, Copy)]
+pub struct BlockChanged {
+    pub pos: IVec3,
+    pub old: Block,
+    pub new: Block,
+    pub cause: BlockChangeCause,
+}
+
+/// Forwards the changes the manager collected since last frame as events
+pub fn emit_block_changes(
+    mut cm: ResMut<ChunkManager>,
+    mut events: EventWriter<BlockChanged>,
+) {
+    let changes = cm.drain_changes();
+    if !changes.is_empty() {
+        events.write_batch(changes);
+    }
+}
@@ -4,17 +4,28 @@ use bevy::prelude::*;
 #[derive(Clone)]
 pub struct Chunk {
     pub blocks: Vec<Block>,
-    pub position: IVec3
+    pub position: IVec3,
+    // Set when a block changes, cleared once the chunk's mesh has been rebuilt
+    dirty: bool,
+    // Number of block changes since the chunk was created
+    modifications: u32
 }
 
 impl Chunk {
     pub fn new(position: IVec3) -> Self {
         Self {
             blocks: vec![AIR; CHUNK_VOLUME],
-            position
+            position,
+            dirty: false,
+            modifications: 0
         }
     }
 
+    /// World position of the block at local (0, 0, 0)
+    pub fn world_origin(&self) -> IVec3 {
+        self.position * CHUNK_SIZE as i32
+    }
+
     #[inline]
     fn index(x: usize, y: usize, z: usize) -> usize {
         x + CHUNK_SIZE * (y + CHUNK_SIZE * z)
@@ -22,7 +33,12 @@ impl Chunk {
 
     pub fn set_block(&mut self, x: usize, y: usize, z: usize, block: Block) {
         let idx = Self::index(x, y, z);
+        if self.blocks[idx].id == block.id {
+            return;
+        }
         self.blocks[idx] = block;
+        self.dirty = true;
+        self.modifications = self.modifications.wrapping_add(1);
     }
 
     pub fn get_block(&self, x: usize, y: usize, z: usize) -> Block {
@@ -30,6 +46,23 @@ impl Chunk {
         self.blocks[idx]
     }
 
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Forces a remesh, e.g. when a neighbour's border changed
+    pub fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+
+    pub fn mark_clean(&mut self) {
+        self.dirty = false;
+    }
+
+    pub fn modifications(&self) -> u32 {
+        self.modifications
+    }
+
     // pub fn in_bounds(x: i32, y: i32, z: i32) -> bool {
     //     (0..CHUNK_SIZE as i32).contains(&x)
     //         && (0..CHUNK_SIZE as i32).contains(&y)
@@ -0,0 +1,176 @@
+use std::collections::{HashSet, VecDeque};
+
+use bevy::prelude::*;
+
+use crate::player::camera::PlayerCamera;
+use crate::voxel::{
+    chunk::Chunk,
+    lod::ChunkLod,
+    manager::ChunkManager,
+    types::{CHUNK_SIZE, CHUNK_VOLUME}
+};
+
+// Chunk faces, indexed so that `i ^ 1` is the opposite face
+const FACE_DIRS: [IVec3; 6] = [
+    IVec3::X, IVec3::NEG_X,
+    IVec3::Y, IVec3::NEG_Y,
+    IVec3::Z, IVec3::NEG_Z,
+];
+
+#[inline]
+fn opposite(face: usize) -> usize {
+    face ^ 1
+}
+
+/// Which pairs of chunk faces are connected through non-solid blocks
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub struct ChunkConnectivity(u64);
+
+impl ChunkConnectivity {
+    const ALL: Self = Self(u64::MAX);
+
+    pub fn connected(&self, a: usize, b: usize) -> bool {
+        self.0 & (1 << (a * 6 + b)) != 0
+    }
+
+    fn connect_all(&mut self, faces: u8) {
+        for a in 0..6 {
+            for b in 0..6 {
+                if faces & (1 << a) != 0 && faces & (1 << b) != 0 {
+                    self.0 |= 1 << (a * 6 + b);
+                }
+            }
+        }
+    }
+
+    /// Flood fills every pocket of see-through blocks and records the faces each one touches
+    pub fn compute(chunk: &Chunk) -> Self {
+        let open = |idx: usize| !chunk.blocks[idx].is_solid();
+        let open_count = (0..CHUNK_VOLUME).filter(|&i| open(i)).count();
+        if open_count == CHUNK_VOLUME {
+            return Self::ALL;
+        }
+
+        let mut result = Self::default();
+        if open_count == 0 {
+            return result;
+        }
+
+        let n = CHUNK_SIZE as i32;
+        let index = |p: IVec3| (p.x + n * (p.y + n * p.z)) as usize;
+        let mut visited = vec![false; CHUNK_VOLUME];
+        let mut queue = VecDeque::new();
+
+        for start in 0..CHUNK_VOLUME {
+            if visited[start] || !open(start) {
+                continue;
+            }
+
+            let mut faces = 0u8;
+            visited[start] = true;
+            let s = start as i32;
+            queue.push_back(IVec3::new(s % n, (s / n) % n, s / (n * n)));
+
+            while let Some(p) = queue.pop_front() {
+                for (face, dir) in FACE_DIRS.iter().enumerate() {
+                    let next = p + *dir;
+                    if next.cmplt(IVec3::ZERO).any() || next.cmpge(IVec3::splat(n)).any() {
+                        faces |= 1 << face;
+                        continue;
+                    }
+
+                    let idx = index(next);
+                    if !visited[idx] && open(idx) {
+                        visited[idx] = true;
+                        queue.push_back(next);
+                    }
+                }
+            }
+
+            result.connect_all(faces);
+        }
+
+        result
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct ChunkCullingStats {
+    pub total: usize,
+    // Hidden by the connectivity graph this frame
+    pub occluded: usize,
+    // Left visible by the graph but rejected by frustum culling last frame
+    pub outside_frustum: usize,
+}
+
+/// Cave culling: walks chunks outward from the camera's chunk, only stepping from the face
+/// a chunk was entered through to faces it connects to, and never back toward the camera.
+/// Chunk entities the walk never reaches are hidden.
+pub fn cull_occluded_chunks(
+    mut cm: ResMut<ChunkManager>,
+    mut stats: ResMut<ChunkCullingStats>,
+    camera: Query<&Transform, With<PlayerCamera>>,
+    mut chunks: Query<(&ChunkLod, &mut Visibility, &ViewVisibility)>,
+) {
+    let Ok(camera) = camera.single() else { return };
+    let (start, _) = ChunkManager::world_to_local(camera.translation.floor().as_ivec3());
+
+    let mut reachable = HashSet::new();
+    if cm.get_chunk(start).is_some() {
+        // (chunk, face it was entered through, directions travelled so far)
+        let mut queue = VecDeque::from([(start, None::<usize>, 0u8)]);
+        reachable.insert(start);
+
+        while let Some((position, entered, travelled)) = queue.pop_front() {
+            let Some(connectivity) = cm.connectivity(position) else { continue };
+
+            for (face, dir) in FACE_DIRS.iter().enumerate() {
+                // Moving back toward the camera can never reveal anything new
+                if travelled & (1 << opposite(face)) != 0 {
+                    continue;
+                }
+                if entered.is_some_and(|e| !connectivity.connected(e, face)) {
+                    continue;
+                }
+
+                let next = position + *dir;
+                if cm.get_chunk(next).is_some() && reachable.insert(next) {
+                    queue.push_back((next, Some(opposite(face)), travelled | (1 << face)));
+                }
+            }
+        }
+    }
+
+    let culling = !reachable.is_empty();
+    *stats = ChunkCullingStats::default();
+
+    for (lod, mut visibility, view_visibility) in chunks.iter_mut() {
+        stats.total += 1;
+
+        let visible = !culling || reachable.contains(&lod.position);
+        if visible {
+            visibility.set_if_neq(Visibility::Inherited);
+            if !view_visibility.get() {
+                stats.outside_frustum += 1;
+            }
+        } else {
+            visibility.set_if_neq(Visibility::Hidden);
+            stats.occluded += 1;
+        }
+    }
+}
+
+pub fn log_culling_stats(
+    keys: Res<ButtonInput<KeyCode>>,
+    stats: Res<ChunkCullingStats>,
+) {
+    if keys.just_pressed(KeyCode::F3) {
+        info!(
+            "📊 Chunks: {} total, {} occluded, {} outside frustum, {} drawn",
+            stats.total,
+            stats.occluded,
+            stats.outside_frustum,
+            stats.total - stats.occluded - stats.outside_frustum,
+        );
+    }
+}
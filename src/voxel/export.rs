@@ -0,0 +1,211 @@
+use bevy::{
+    prelude::*,
+    render::mesh::{Indices, VertexAttributeValues},
+};
+
+use crate::voxel::{
+    manager::ChunkManager,
+    material::ATTRIBUTE_PACKED_VERTEX,
+    mesher::{generate_mesh_for_chunk, unpack_vertex, UnpackedVertex},
+    types::{AIR, BLOCKS}
+};
+
+const GLB_MAGIC: u32 = 0x4654_6C67;
+const GLB_JSON_CHUNK: u32 = 0x4E4F_534A;
+const GLB_BIN_CHUNK: u32 = 0x004E_4942;
+const GL_FLOAT: u32 = 5126;
+const GL_UNSIGNED_INT: u32 = 5125;
+const GL_ARRAY_BUFFER: u32 = 34962;
+const GL_ELEMENT_ARRAY_BUFFER: u32 = 34963;
+
+/// Full resolution chunk meshes unpacked into plain triangles, for writing to disk.
+/// Colours are linear with ambient occlusion baked in, since offline renderers do their own lighting.
+#[derive(Default)]
+pub struct ExportMesh {
+    pub positions: Vec<Vec3>,
+    pub normals: Vec<Vec3>,
+    pub colors: Vec<Vec4>,
+    pub indices: Vec<u32>,
+}
+
+impl ExportMesh {
+    /// Meshes every loaded chunk between two chunk positions, placed relative to `origin` in world blocks
+    pub fn from_chunks(cm: &ChunkManager, min_chunk: IVec3, max_chunk: IVec3, origin: IVec3) -> Self {
+        let mut export = Self::default();
+        for z in min_chunk.z..=max_chunk.z {
+            for y in min_chunk.y..=max_chunk.y {
+                for x in min_chunk.x..=max_chunk.x {
+                    let Some(chunk) = cm.get_chunk(IVec3::new(x, y, z)) else { continue };
+                    let mesh = generate_mesh_for_chunk(chunk, 1).mesh;
+                    export.append(&mesh, chunk.world_origin() - origin);
+                }
+            }
+        }
+        export
+    }
+
+    fn append(&mut self, mesh: &Mesh, offset: IVec3) {
+        let Some(VertexAttributeValues::Uint32x2(packed)) = mesh.attribute(ATTRIBUTE_PACKED_VERTEX) else { return };
+        let Some(Indices::U32(indices)) = mesh.indices() else { return };
+
+        let base = self.positions.len() as u32;
+        for vertex in packed.iter().map(|p| unpack_vertex(*p)) {
+            self.positions.push((vertex.position + offset).as_vec3());
+            self.normals.push(vertex.normal.as_vec3());
+            self.colors.push(vertex_color(&vertex));
+        }
+        self.indices.extend(indices.iter().map(|i| base + i));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    /// Wavefront OBJ with the common `v x y z r g b` vertex colour extension (sRGB)
+    pub fn to_obj(&self) -> String {
+        let mut obj = String::from("# Gamecraft world export\no world\n");
+        for (position, color) in self.positions.iter().zip(&self.colors) {
+            let srgb = Srgba::from(LinearRgba::from_vec4(*color));
+            obj.push_str(&format!(
+                "v {} {} {} {:.4} {:.4} {:.4}\n",
+                position.x, position.y, position.z, srgb.red, srgb.green, srgb.blue
+            ));
+        }
+        for normal in &self.normals {
+            obj.push_str(&format!("vn {} {} {}\n", normal.x, normal.y, normal.z));
+        }
+        // OBJ indices start at 1; every vertex has its own normal, so they share an index
+        for triangle in self.indices.chunks_exact(3) {
+            let [a, b, c] = [triangle[0] + 1, triangle[1] + 1, triangle[2] + 1];
+            obj.push_str(&format!("f {a}//{a} {b}//{b} {c}//{c}\n"));
+        }
+        obj
+    }
+
+    /// Binary glTF 2.0 with positions, normals, COLOR_0 and u32 indices in one buffer
+    pub fn to_glb(&self) -> Vec<u8> {
+        let mut bin = Vec::new();
+        let mut views = Vec::new();
+
+        let mut push_view = |bin: &mut Vec<u8>, data: Vec<u8>, target: u32| {
+            views.push(format!(
+                r#"{{"buffer":0,"byteOffset":{},"byteLength":{},"target":{}}}"#,
+                bin.len(), data.len(), target
+            ));
+            bin.extend(data);
+        };
+        push_view(&mut bin, floats(self.positions.iter().flat_map(|v| v.to_array())), GL_ARRAY_BUFFER);
+        push_view(&mut bin, floats(self.normals.iter().flat_map(|v| v.to_array())), GL_ARRAY_BUFFER);
+        push_view(&mut bin, floats(self.colors.iter().flat_map(|v| v.to_array())), GL_ARRAY_BUFFER);
+        push_view(&mut bin, self.indices.iter().flat_map(|i| i.to_le_bytes()).collect(), GL_ELEMENT_ARRAY_BUFFER);
+
+        // POSITION requires bounds
+        let (min, max) = self.positions.iter()
+            .fold((Vec3::INFINITY, Vec3::NEG_INFINITY), |(min, max), p| (min.min(*p), max.max(*p)));
+        let count = self.positions.len();
+
+        let json = format!(
+            concat!(
+                r#"{{"asset":{{"version":"2.0","generator":"Gamecraft"}},"scene":0,"scenes":[{{"nodes":[0]}}],"#,
+                r#""nodes":[{{"mesh":0,"name":"world"}}],"#,
+                r#""meshes":[{{"primitives":[{{"attributes":{{"POSITION":0,"NORMAL":1,"COLOR_0":2}},"indices":3}}]}}],"#,
+                r#""buffers":[{{"byteLength":{}}}],"bufferViews":[{}],"accessors":["#,
+                r#"{{"bufferView":0,"componentType":{},"count":{},"type":"VEC3","min":[{},{},{}],"max":[{},{},{}]}},"#,
+                r#"{{"bufferView":1,"componentType":{},"count":{},"type":"VEC3"}},"#,
+                r#"{{"bufferView":2,"componentType":{},"count":{},"type":"VEC4"}},"#,
+                r#"{{"bufferView":3,"componentType":{},"count":{},"type":"SCALAR"}}]}}"#,
+            ),
+            bin.len(), views.join(","),
+            GL_FLOAT, count, min.x, min.y, min.z, max.x, max.y, max.z,
+            GL_FLOAT, count,
+            GL_FLOAT, count,
+            GL_UNSIGNED_INT, self.indices.len(),
+        );
+
+        let mut json = json.into_bytes();
+        // Chunks are 4-byte aligned: JSON pads with spaces, binary with zeros
+        json.resize(json.len().next_multiple_of(4), b' ');
+        bin.resize(bin.len().next_multiple_of(4), 0);
+
+        let total = 12 + 8 + json.len() + 8 + bin.len();
+        let mut glb = Vec::with_capacity(total);
+        for word in [GLB_MAGIC, 2, total as u32, json.len() as u32, GLB_JSON_CHUNK] {
+            glb.extend(word.to_le_bytes());
+        }
+        glb.extend(json);
+        for word in [bin.len() as u32, GLB_BIN_CHUNK] {
+            glb.extend(word.to_le_bytes());
+        }
+        glb.extend(bin);
+        glb
+    }
+}
+
+fn floats(values: impl Iterator<Item = f32>) -> Vec<u8> {
+    values.flat_map(f32::to_le_bytes).collect()
+}
+
+// Same shading the chunk shader applies per vertex
+fn vertex_color(vertex: &UnpackedVertex) -> Vec4 {
+    let block = BLOCKS.get(vertex.texture as usize).copied().unwrap_or(AIR);
+    let shade = (0.5 + 0.5 * vertex.ao as f32 / 3.0) * (vertex.light as f32 / 15.0);
+    let color = block.color.to_linear();
+    Vec4::new(color.red * shade, color.green * shade, color.blue * shade, color.alpha)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::voxel::{chunk::Chunk, mesher::pack_vertex, types::STONE};
+
+    fn one_block() -> ExportMesh {
+        let mut cm = ChunkManager::default();
+        let mut chunk = Chunk::new(IVec3::ZERO);
+        chunk.set_block(3, 4, 5, STONE);
+        cm.insert_chunk(chunk);
+        ExportMesh::from_chunks(&cm, IVec3::ZERO, IVec3::ZERO, IVec3::ZERO)
+    }
+
+    #[test]
+    fn packed_vertices_unpack_to_what_went_in() {
+        let cases = [(IVec3::new(-8, 0, 247), 0, IVec3::X), (IVec3::splat(16), 3, IVec3::NEG_Y), (IVec3::new(0, -8, 5), 5, IVec3::NEG_Z)];
+        for (pos, normal, direction) in cases {
+            let vertex = unpack_vertex(pack_vertex(pos, normal, 2, STONE.id, 15));
+            assert_eq!(vertex.position, pos);
+            assert_eq!(vertex.normal, direction);
+            assert_eq!((vertex.ao, vertex.texture, vertex.light), (2, STONE.id, 15));
+        }
+    }
+
+    #[test]
+    fn obj_of_one_block_has_six_quads() {
+        let obj = one_block().to_obj();
+        let count = |prefix: &str| obj.lines().filter(|l| l.starts_with(prefix)).count();
+        assert_eq!(count("v "), 6 * 4);
+        assert_eq!(count("vn "), 6 * 4);
+        assert_eq!(count("f "), 6 * 2);
+        assert!(obj.lines().any(|l| l.starts_with("v 3 4 5 ")));
+    }
+
+    #[test]
+    fn glb_chunks_are_sized_and_aligned() {
+        let glb = one_block().to_glb();
+        let word = |at: usize| u32::from_le_bytes(glb[at..at + 4].try_into().unwrap()) as usize;
+
+        assert_eq!(word(0) as u32, GLB_MAGIC);
+        assert_eq!(word(4), 2);
+        assert_eq!(word(8), glb.len());
+
+        let json_len = word(12);
+        assert_eq!(word(16) as u32, GLB_JSON_CHUNK);
+        let bin_at = 20 + json_len;
+        let bin_len = word(bin_at);
+        assert_eq!(word(bin_at + 4) as u32, GLB_BIN_CHUNK);
+        assert_eq!(json_len % 4, 0);
+        assert_eq!(bin_len % 4, 0);
+        assert_eq!(bin_at + 8 + bin_len, glb.len());
+
+        // 24 vertices of position, normal and colour floats, then 36 indices
+        assert_eq!(bin_len, 24 * (3 + 3 + 4) * 4 + 36 * 4);
+    }
+}
@@ -0,0 +1,129 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+use crate::voxel::{
+    change::BlockChangeCause,
+    loading::WORLD_BOTTOM,
+    manager::ChunkManager,
+    tick::{ScheduledUpdates, WorldTick},
+    types::Block
+};
+
+// Blocks per second squared
+const FALL_ACCELERATION: f32 = 24.0;
+const TERMINAL_VELOCITY: f32 = 40.0;
+// Falling blocks that drop below this height are discarded
+const VOID_DEPTH: f32 = WORLD_BOTTOM as f32 - 64.0;
+
+/// Sent when an unsupported gravity block is removed from the world
+#[derive(Event)]
+pub struct BlockDetached {
+    pub pos: IVec3,
+    pub block: Block,
+}
+
+#[derive(Component)]
+pub struct FallingBlock {
+    pub block: Block,
+    pub velocity: f32,
+}
+
+#[derive(Resource)]
+pub struct FallingBlockAssets {
+    mesh: Handle<Mesh>,
+    // One material per block id, shared by every falling entity of that type
+    materials: HashMap<u8, Handle<StandardMaterial>>,
+}
+
+pub fn setup_falling_block_assets(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    commands.insert_resource(FallingBlockAssets {
+        mesh: meshes.add(Cuboid::new(1.0, 1.0, 1.0)),
+        materials: HashMap::new(),
+    });
+}
+
+pub fn spawn_falling_blocks(
+    mut commands: Commands,
+    mut events: EventReader<BlockDetached>,
+    mut assets: ResMut<FallingBlockAssets>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    for event in events.read() {
+        let material = assets.materials
+            .entry(event.block.id)
+            .or_insert_with(|| materials.add(StandardMaterial {
+                base_color: event.block.color,
+                ..Default::default()
+            }))
+            .clone();
+
+        commands.spawn((
+            Mesh3d(assets.mesh.clone()),
+            MeshMaterial3d(material),
+            // Entity origin is the block centre
+            Transform::from_translation(event.pos.as_vec3() + Vec3::splat(0.5)),
+            FallingBlock { block: event.block, velocity: 0.0 },
+        ));
+    }
+}
+
+pub fn update_falling_blocks(
+    mut commands: Commands,
+    time: Res<Time>,
+    tick: Res<WorldTick>,
+    mut cm: ResMut<ChunkManager>,
+    mut scheduled: ResMut<ScheduledUpdates>,
+    mut query: Query<(Entity, &mut Transform, &mut FallingBlock)>,
+) {
+    let dt = time.delta_secs();
+
+    for (entity, mut transform, mut falling) in query.iter_mut() {
+        falling.velocity = (falling.velocity - FALL_ACCELERATION * dt).max(-TERMINAL_VELOCITY);
+
+        let current = transform.translation;
+        let next_y = current.y + falling.velocity * dt;
+        let x = current.x.floor() as i32;
+        let z = current.z.floor() as i32;
+
+        // Walk every cell the bottom face passes through this frame so fast blocks can't tunnel.
+        // Unloaded cells above the world bottom stop it too, until their chunk is generated
+        let from = (current.y - 0.5).floor() as i32;
+        let to = (next_y - 0.5).floor() as i32;
+        let stop = (to..from).rev()
+            .map(|y| IVec3::new(x, y, z))
+            .find(|cell| cell.y >= WORLD_BOTTOM && cm.get_block_world(*cell).is_none_or(|b| b.is_solid()));
+
+        match stop.map(|cell| (cell, cm.get_block_world(cell))) {
+            Some((ground, Some(_))) => {
+                // Settle in the first free cell above the ground, in case the one on top got filled
+                let free = (ground.y + 1..)
+                    .map(|y| IVec3::new(x, y, z))
+                    .map(|cell| (cell, cm.get_block_world(cell)))
+                    .find(|(_, block)| block.is_none_or(|b| !b.is_solid()));
+                if let Some((rest, Some(_))) = free {
+                    cm.set_block_world(rest, falling.block, BlockChangeCause::Physics);
+                    scheduled.notify_neighbours(rest, tick.0);
+                    commands.entity(entity).despawn();
+                } else {
+                    // The free cell is in an unloaded chunk, so hang on until it loads
+                    falling.velocity = 0.0;
+                }
+            }
+            Some((unloaded, None)) => {
+                // Rest on top of the unloaded cell without writing anything yet
+                transform.translation.y = unloaded.y as f32 + 1.5;
+                falling.velocity = 0.0;
+            }
+            None if next_y < VOID_DEPTH => {
+                commands.entity(entity).despawn();
+            }
+            None => {
+                transform.translation.y = next_y;
+            }
+        }
+    }
+}
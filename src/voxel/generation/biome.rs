@@ -0,0 +1,60 @@
+use crate::voxel::{
+    generation::{noise::value_noise_2d, trees::TreeKind},
+    types::{Block, DIRT, GRASS, SAND}
+};
+
+// Size in blocks of the noise features that decide biomes
+const BIOME_SCALE: f32 = 96.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Biome {
+    Plains,
+    Forest,
+    Taiga,
+    Desert,
+}
+
+impl Biome {
+    pub fn at(seed: u64, x: i32, z: i32) -> Self {
+        let n = value_noise_2d(seed, x as f32, z as f32, BIOME_SCALE);
+        match n {
+            n if n < 0.25 => Biome::Desert,
+            n if n < 0.55 => Biome::Plains,
+            n if n < 0.8 => Biome::Forest,
+            _ => Biome::Taiga,
+        }
+    }
+
+    pub fn surface_block(&self) -> Block {
+        match self {
+            Biome::Desert => SAND,
+            _ => GRASS,
+        }
+    }
+
+    pub fn subsurface_block(&self) -> Block {
+        match self {
+            Biome::Desert => SAND,
+            _ => DIRT,
+        }
+    }
+
+    /// Chance per column of a tree rooted there
+    pub fn tree_density(&self) -> f32 {
+        match self {
+            Biome::Plains => 0.004,
+            Biome::Forest => 0.03,
+            Biome::Taiga => 0.02,
+            Biome::Desert => 0.0,
+        }
+    }
+
+    /// Picks a tree variant from a roll in `[0, 1)`
+    pub fn tree_kind(&self, roll: f32) -> TreeKind {
+        match self {
+            Biome::Forest if roll < 0.25 => TreeKind::Shrub,
+            Biome::Taiga => TreeKind::Spruce,
+            _ => TreeKind::Oak,
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use std::f32::consts::{PI, TAU};
+
+use bevy::prelude::*;
+
+use crate::voxel::{
+    chunk::Chunk,
+    generation::{noise::{fbm_3d, hash3}, GenerationPass, PassContext},
+    random::WorldRng,
+    types::{AIR, CHUNK_SIZE}
+};
+
+// Noise caves
+const CAVE_NOISE_SCALE: f32 = 24.0;
+const CAVE_NOISE_OCTAVES: u32 = 2;
+// Higher values carve fewer, smaller caverns
+const CAVE_THRESHOLD: f32 = 0.68;
+// Keeps noise caverns from eating the surface away
+const CAVE_SURFACE_MARGIN: i32 = 4;
+
+// Worm caves
+const WORM_CHANCE: f32 = 0.25;
+const WORM_MIN_STEPS: i32 = 40;
+const WORM_MAX_STEPS: i32 = 90;
+const WORM_MIN_RADIUS: f32 = 1.2;
+const WORM_MAX_RADIUS: f32 = 3.0;
+// How many chunks away a worm can start and still reach this chunk: every step moves
+// one block, so the longest worm plus its widest sphere, rounded up to whole chunks
+const WORM_REACH_CHUNKS: i32 = (WORM_MAX_STEPS + WORM_MAX_RADIUS as i32) / CHUNK_SIZE as i32 + 1;
+
+/// Carves cheese-style caverns wherever 3D noise is above a threshold
+pub struct NoiseCavePass {
+    pub seed: u64,
+}
+
+impl GenerationPass for NoiseCavePass {
+    fn generate(&self, chunk: &mut Chunk, ctx: &PassContext) {
+        let origin = chunk.world_origin();
+
+        for z in 0..CHUNK_SIZE {
+            for x in 0..CHUNK_SIZE {
+                let ceiling = ctx.surface(x, z) - CAVE_SURFACE_MARGIN;
+
+                for y in 0..CHUNK_SIZE {
+                    let pos = origin + IVec3::new(x as i32, y as i32, z as i32);
+                    if pos.y > ceiling {
+                        break;
+                    }
+
+                    let density = fbm_3d(self.seed, pos.as_vec3(), CAVE_NOISE_SCALE, CAVE_NOISE_OCTAVES);
+                    if density > CAVE_THRESHOLD && chunk.get_block(x, y, z).is_solid() {
+                        chunk.set_block(x, y, z, AIR);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Carves long tunnels by walking a sphere along a wandering path.
+/// Worms are seeded per source chunk and replayed from every chunk they can reach,
+/// so each chunk carves its share without needing its neighbours.
+pub struct WormCavePass {
+    pub seed: u64,
+}
+
+impl WormCavePass {
+    fn carve_worm(&self, chunk: &mut Chunk, source: IVec3) {
+        let mut rng = WorldRng::new(hash3(self.seed, source.x, source.y, source.z));
+        if rng.next_f32() >= WORM_CHANCE {
+            return;
+        }
+
+        let size = CHUNK_SIZE as f32;
+        let mut pos = source.as_vec3() * size
+            + Vec3::new(rng.next_f32(), rng.next_f32(), rng.next_f32()) * size;
+        let mut yaw = rng.next_f32() * TAU;
+        let mut pitch = (rng.next_f32() - 0.5) * 0.5;
+        let steps = rng.range_i32(WORM_MIN_STEPS, WORM_MAX_STEPS);
+
+        for step in 0..steps {
+            // Thin at both ends, widest in the middle
+            let t = step as f32 / steps as f32;
+            let radius = WORM_MIN_RADIUS + (t * PI).sin() * (WORM_MAX_RADIUS - WORM_MIN_RADIUS);
+            carve_sphere(chunk, pos, radius);
+
+            pos += Vec3::new(yaw.cos() * pitch.cos(), pitch.sin(), yaw.sin() * pitch.cos());
+            yaw += (rng.next_f32() - 0.5) * 0.5;
+            pitch = pitch * 0.7 + (rng.next_f32() - 0.5) * 0.3;
+        }
+    }
+}
+
+impl GenerationPass for WormCavePass {
+    fn generate(&self, chunk: &mut Chunk, _ctx: &PassContext) {
+        let r = WORM_REACH_CHUNKS;
+        for dz in -r..=r {
+            for dy in -r..=r {
+                for dx in -r..=r {
+                    self.carve_worm(chunk, chunk.position + IVec3::new(dx, dy, dz));
+                }
+            }
+        }
+    }
+}
+
+fn carve_sphere(chunk: &mut Chunk, center: Vec3, radius: f32) {
+    let origin = chunk.world_origin().as_vec3();
+    let local = center - origin;
+    let max = CHUNK_SIZE as f32 - 1.0;
+
+    // Skip spheres that don't touch this chunk at all
+    let min = (local - radius).floor().max(Vec3::ZERO);
+    let upper = (local + radius).ceil().min(Vec3::splat(max));
+    if min.cmpgt(upper).any() {
+        return;
+    }
+
+    let radius_sq = radius * radius;
+    for z in min.z as usize..=upper.z as usize {
+        for y in min.y as usize..=upper.y as usize {
+            for x in min.x as usize..=upper.x as usize {
+                let cell = Vec3::new(x as f32, y as f32, z as f32) + 0.5;
+                if cell.distance_squared(local) <= radius_sq && chunk.get_block(x, y, z).is_solid() {
+                    chunk.set_block(x, y, z, AIR);
+                }
+            }
+        }
+    }
+}
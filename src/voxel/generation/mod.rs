@@ -0,0 +1,159 @@
+pub mod biome;
+pub mod caves;
+pub mod noise;
+pub mod ores;
+pub mod pockets;
+pub mod schematic;
+pub mod terrain;
+pub mod trees;
+
+use bevy::prelude::*;
+
+use crate::voxel::{
+    chunk::Chunk,
+    generation::{
+        biome::Biome,
+        caves::{NoiseCavePass, WormCavePass},
+        noise::hash3,
+        ores::{OrePass, DEFAULT_ORES},
+        pockets::FluidPocketPass,
+        schematic::Schematic,
+        terrain::{surface_height, TerrainPass},
+        trees::place_tree
+    },
+    manager::ChunkManager,
+    random::WorldRng,
+    types::CHUNK_SIZE
+};
+
+const DEFAULT_SEED: u64 = 0x5EED_CAFE;
+// Salts giving every pass its own fixed seed, so changing one pass never reshuffles another
+const FEATURE_SALT: u64 = 0xFEA7_0001;
+const ORE_SALT: u64 = 0x04E5_0002;
+const NOISE_CAVE_SALT: u64 = 0xCA7E_0003;
+const WORM_CAVE_SALT: u64 = 0x3047_0004;
+const POCKET_SALT: u64 = 0x90C7_0005;
+
+// Chance per plains chunk of a well
+const WELL_CHANCE: f32 = 0.05;
+
+const WELL_SCHEMATIC: &str = include_str!("../../../assets/schematics/well.txt");
+
+/// Per-chunk data computed once and shared by every pass
+pub struct PassContext {
+    // Terrain surface height of each column, indexed x + z * CHUNK_SIZE
+    surface: [i32; CHUNK_SIZE * CHUNK_SIZE],
+}
+
+impl PassContext {
+    pub fn surface(&self, x: usize, z: usize) -> i32 {
+        self.surface[x + CHUNK_SIZE * z]
+    }
+}
+
+/// One step of chunk generation; passes only touch the chunk they are given
+pub trait GenerationPass: Send + Sync {
+    fn generate(&self, chunk: &mut Chunk, ctx: &PassContext);
+}
+
+fn pass_seed(seed: u64, salt: u64) -> u64 {
+    hash3(seed ^ salt, 0, 0, 0)
+}
+
+#[derive(Resource)]
+pub struct WorldGenerator {
+    pub seed: u64,
+    // Run in order on every new chunk, before features are placed
+    passes: Vec<Box<dyn GenerationPass>>,
+    well: Schematic,
+}
+
+impl Default for WorldGenerator {
+    fn default() -> Self {
+        Self::new(DEFAULT_SEED)
+    }
+}
+
+impl WorldGenerator {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            passes: vec![
+                Box::new(TerrainPass { seed }),
+                Box::new(OrePass { seed: pass_seed(seed, ORE_SALT), ores: &DEFAULT_ORES }),
+                Box::new(NoiseCavePass { seed: pass_seed(seed, NOISE_CAVE_SALT) }),
+                Box::new(WormCavePass { seed: pass_seed(seed, WORM_CAVE_SALT) }),
+                Box::new(FluidPocketPass { seed: pass_seed(seed, POCKET_SALT) }),
+            ],
+            well: Schematic::parse(WELL_SCHEMATIC).expect("built-in well schematic is valid"),
+        }
+    }
+
+    pub fn surface_height(&self, x: i32, z: i32) -> i32 {
+        surface_height(self.seed, x, z)
+    }
+
+    pub fn generate_chunk(&self, cm: &mut ChunkManager, position: IVec3) {
+        let ctx = self.pass_context(position);
+        cm.insert_chunk(self.generate_terrain(position, &ctx));
+        self.place_features(cm, position, &ctx);
+    }
+
+    fn pass_context(&self, position: IVec3) -> PassContext {
+        let origin = position * CHUNK_SIZE as i32;
+        let mut surface = [0; CHUNK_SIZE * CHUNK_SIZE];
+
+        for z in 0..CHUNK_SIZE {
+            for x in 0..CHUNK_SIZE {
+                surface[x + CHUNK_SIZE * z] = self.surface_height(origin.x + x as i32, origin.z + z as i32);
+            }
+        }
+
+        PassContext { surface }
+    }
+
+    fn generate_terrain(&self, position: IVec3, ctx: &PassContext) -> Chunk {
+        let mut chunk = Chunk::new(position);
+        for pass in self.passes.iter() {
+            pass.generate(&mut chunk, ctx);
+        }
+        chunk
+    }
+
+    // Trees and structures; anything crossing into an ungenerated neighbour is deferred by the manager
+    fn place_features(&self, cm: &mut ChunkManager, position: IVec3, ctx: &PassContext) {
+        let origin = position * CHUNK_SIZE as i32;
+        let in_chunk = |y: i32| (origin.y..origin.y + CHUNK_SIZE as i32).contains(&y);
+
+        let mut rng = WorldRng::new(hash3(pass_seed(self.seed, FEATURE_SALT), position.x, position.y, position.z));
+
+        for z in 0..CHUNK_SIZE {
+            for x in 0..CHUNK_SIZE {
+                // Features are rooted in the chunk holding the column's surface
+                let surface = ctx.surface(x, z);
+                if !in_chunk(surface) {
+                    continue;
+                }
+
+                let column = IVec3::new(origin.x + x as i32, surface, origin.z + z as i32);
+                let biome = Biome::at(self.seed, column.x, column.z);
+                // Caves may have carved the surface away
+                let grounded = cm.get_block_world(column).is_some_and(|b| b.id == biome.surface_block().id);
+
+                if grounded && rng.next_f32() < biome.tree_density() {
+                    let kind = biome.tree_kind(rng.next_f32());
+                    place_tree(cm, column + IVec3::Y, kind, &mut rng);
+                }
+            }
+        }
+
+        let half = CHUNK_SIZE / 2;
+        let surface = ctx.surface(half, half);
+        let centre = origin + IVec3::new(half as i32, 0, half as i32);
+        let is_plains = Biome::at(self.seed, centre.x, centre.z) == Biome::Plains;
+        if in_chunk(surface) && is_plains && rng.next_f32() < WELL_CHANCE {
+            let offset = self.well.size.as_ivec3() / 2;
+            self.well.place(cm, IVec3::new(centre.x - offset.x, surface, centre.z - offset.z));
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use bevy::prelude::*;
+
+// Hash-based value noise, deterministic for a given seed
+
+/// Mixes a seed and integer coordinates into a well distributed 64-bit value
+pub fn hash3(seed: u64, x: i32, y: i32, z: i32) -> u64 {
+    let mut h = seed
+        ^ (x as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        ^ (y as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F)
+        ^ (z as u64).wrapping_mul(0x1656_67B1_9E37_79F9);
+    h = (h ^ (h >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    h = (h ^ (h >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    h ^ (h >> 31)
+}
+
+/// Hash mapped to `[0, 1)`
+pub fn hash_unit(seed: u64, x: i32, y: i32, z: i32) -> f32 {
+    (hash3(seed, x, y, z) >> 40) as f32 / (1u64 << 24) as f32
+}
+
+#[inline]
+pub fn smoothstep(t: f32) -> f32 {
+    t * t * (3.0 - 2.0 * t)
+}
+
+#[inline]
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+/// 2D value noise in `[0, 1)`, one lattice point per `scale` blocks
+pub fn value_noise_2d(seed: u64, x: f32, z: f32, scale: f32) -> f32 {
+    let (x, z) = (x / scale, z / scale);
+    let (x0, z0) = (x.floor() as i32, z.floor() as i32);
+    let (tx, tz) = (smoothstep(x - x0 as f32), smoothstep(z - z0 as f32));
+
+    let c00 = hash_unit(seed, x0, 0, z0);
+    let c10 = hash_unit(seed, x0 + 1, 0, z0);
+    let c01 = hash_unit(seed, x0, 0, z0 + 1);
+    let c11 = hash_unit(seed, x0 + 1, 0, z0 + 1);
+
+    lerp(lerp(c00, c10, tx), lerp(c01, c11, tx), tz)
+}
+
+/// Fractal sum of 2D value noise, normalised back to `[0, 1)`
+pub fn fbm_2d(seed: u64, x: f32, z: f32, scale: f32, octaves: u32) -> f32 {
+    let mut total = 0.0;
+    let mut amplitude = 1.0;
+    let mut max = 0.0;
+    let mut scale = scale;
+
+    for octave in 0..octaves {
+        total += value_noise_2d(seed.wrapping_add(octave as u64), x, z, scale) * amplitude;
+        max += amplitude;
+        amplitude *= 0.5;
+        scale *= 0.5;
+    }
+
+    total / max
+}
+
+/// 3D value noise in `[0, 1)`, one lattice point per `scale` blocks
+pub fn value_noise_3d(seed: u64, p: Vec3, scale: f32) -> f32 {
+    let p = p / scale;
+    let base = p.floor();
+    let (x0, y0, z0) = (base.x as i32, base.y as i32, base.z as i32);
+    let t = p - base;
+    let (tx, ty, tz) = (smoothstep(t.x), smoothstep(t.y), smoothstep(t.z));
+
+    let corner = |dx: i32, dy: i32, dz: i32| hash_unit(seed, x0 + dx, y0 + dy, z0 + dz);
+
+    let y0_plane = lerp(
+        lerp(corner(0, 0, 0), corner(1, 0, 0), tx),
+        lerp(corner(0, 0, 1), corner(1, 0, 1), tx),
+        tz,
+    );
+    let y1_plane = lerp(
+        lerp(corner(0, 1, 0), corner(1, 1, 0), tx),
+        lerp(corner(0, 1, 1), corner(1, 1, 1), tx),
+        tz,
+    );
+
+    lerp(y0_plane, y1_plane, ty)
+}
+
+/// Fractal sum of 3D value noise, normalised back to `[0, 1)`
+pub fn fbm_3d(seed: u64, p: Vec3, scale: f32, octaves: u32) -> f32 {
+    let mut total = 0.0;
+    let mut amplitude = 1.0;
+    let mut max = 0.0;
+    let mut scale = scale;
+
+    for octave in 0..octaves {
+        total += value_noise_3d(seed.wrapping_add(octave as u64), p, scale) * amplitude;
+        max += amplitude;
+        amplitude *= 0.5;
+        scale *= 0.5;
+    }
+
+    total / max
+}
@@ -0,0 +1,77 @@
+use bevy::prelude::*;
+
+use crate::voxel::{
+    chunk::Chunk,
+    generation::{noise::hash3, GenerationPass, PassContext},
+    random::WorldRng,
+    types::{Block, CHUNK_SIZE, COAL_ORE, DIAMOND_ORE, GOLD_ORE, IRON_ORE, STONE}
+};
+
+/// Where and how often an ore appears
+pub struct OreVein {
+    pub block: Block,
+    // Inclusive world Y range veins can start in
+    pub min_y: i32,
+    pub max_y: i32,
+    pub veins_per_chunk: u32,
+    // Blocks visited by the random walk of each vein
+    pub vein_size: u32,
+}
+
+// Rarer ores sit deeper, in smaller and fewer veins
+pub const DEFAULT_ORES: [OreVein; 4] = [
+    OreVein { block: COAL_ORE, min_y: 0, max_y: 128, veins_per_chunk: 8, vein_size: 12 },
+    OreVein { block: IRON_ORE, min_y: 0, max_y: 64, veins_per_chunk: 6, vein_size: 8 },
+    OreVein { block: GOLD_ORE, min_y: 0, max_y: 32, veins_per_chunk: 2, vein_size: 6 },
+    OreVein { block: DIAMOND_ORE, min_y: 0, max_y: 16, veins_per_chunk: 1, vein_size: 4 },
+];
+
+const WALK_STEPS: [IVec3; 6] = [
+    IVec3::X, IVec3::NEG_X,
+    IVec3::Y, IVec3::NEG_Y,
+    IVec3::Z, IVec3::NEG_Z,
+];
+
+/// Replaces stone with random-walk ore veins; veins stay inside the chunk that rolled them
+pub struct OrePass {
+    pub seed: u64,
+    pub ores: &'static [OreVein],
+}
+
+impl GenerationPass for OrePass {
+    fn generate(&self, chunk: &mut Chunk, _ctx: &PassContext) {
+        let origin = chunk.world_origin();
+        let top = origin.y + CHUNK_SIZE as i32 - 1;
+        let max = CHUNK_SIZE as i32 - 1;
+
+        for (ore_index, ore) in self.ores.iter().enumerate() {
+            if ore.max_y < origin.y || ore.min_y > top {
+                continue;
+            }
+
+            let seed = hash3(self.seed, chunk.position.x, chunk.position.y, chunk.position.z);
+            let mut rng = WorldRng::new(seed.wrapping_add(ore_index as u64));
+
+            let min_local = (ore.min_y - origin.y).max(0);
+            let max_local = (ore.max_y - origin.y).min(max);
+
+            for _ in 0..ore.veins_per_chunk {
+                let mut pos = IVec3::new(
+                    rng.range_i32(0, max),
+                    rng.range_i32(min_local, max_local),
+                    rng.range_i32(0, max),
+                );
+
+                for _ in 0..ore.vein_size {
+                    let (x, y, z) = (pos.x as usize, pos.y as usize, pos.z as usize);
+                    if chunk.get_block(x, y, z).id == STONE.id {
+                        chunk.set_block(x, y, z, ore.block);
+                    }
+
+                    let step = WALK_STEPS[rng.range(WALK_STEPS.len())];
+                    pos = (pos + step).clamp(IVec3::ZERO, IVec3::splat(max));
+                }
+            }
+        }
+    }
+}
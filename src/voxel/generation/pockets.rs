@@ -0,0 +1,67 @@
+use bevy::prelude::*;
+
+use crate::voxel::{
+    chunk::Chunk,
+    generation::{noise::hash3, GenerationPass, PassContext},
+    random::WorldRng,
+    types::{AIR, CHUNK_SIZE, LAVA, WATER}
+};
+
+const POCKET_CHANCE: f32 = 0.15;
+// Pockets below this height hold lava instead of water
+const LAVA_LEVEL: i32 = 16;
+const POCKET_MIN_RADIUS: i32 = 2;
+const POCKET_MAX_RADIUS: i32 = 4;
+// Keeps pockets fully underground
+const POCKET_SURFACE_MARGIN: i32 = 6;
+
+/// Hollow ellipsoids in stone, lower half filled with water or lava.
+/// Pockets are kept inside their chunk so no neighbour is ever touched.
+pub struct FluidPocketPass {
+    pub seed: u64,
+}
+
+impl GenerationPass for FluidPocketPass {
+    fn generate(&self, chunk: &mut Chunk, ctx: &PassContext) {
+        let mut rng = WorldRng::new(hash3(self.seed, chunk.position.x, chunk.position.y, chunk.position.z));
+        if rng.next_f32() >= POCKET_CHANCE {
+            return;
+        }
+
+        let origin = chunk.world_origin();
+        let max = CHUNK_SIZE as i32 - 1;
+        let radius = IVec3::new(
+            rng.range_i32(POCKET_MIN_RADIUS, POCKET_MAX_RADIUS),
+            rng.range_i32(POCKET_MIN_RADIUS, POCKET_MAX_RADIUS - 1),
+            rng.range_i32(POCKET_MIN_RADIUS, POCKET_MAX_RADIUS),
+        );
+        let center = IVec3::new(
+            rng.range_i32(radius.x, max - radius.x),
+            rng.range_i32(radius.y, max - radius.y),
+            rng.range_i32(radius.z, max - radius.z),
+        );
+
+        let surface = ctx.surface(center.x as usize, center.z as usize);
+        if origin.y + center.y + radius.y > surface - POCKET_SURFACE_MARGIN {
+            return;
+        }
+
+        let fluid = if origin.y + center.y < LAVA_LEVEL { LAVA } else { WATER };
+        let r = radius.as_vec3();
+
+        for z in -radius.z..=radius.z {
+            for y in -radius.y..=radius.y {
+                for x in -radius.x..=radius.x {
+                    let offset = IVec3::new(x, y, z);
+                    if (offset.as_vec3() / r).length_squared() > 1.0 {
+                        continue;
+                    }
+
+                    let p = (center + offset).as_uvec3();
+                    let block = if y <= 0 { fluid } else { AIR };
+                    chunk.set_block(p.x as usize, p.y as usize, p.z as usize, block);
+                }
+            }
+        }
+    }
+}
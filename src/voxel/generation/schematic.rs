@@ -0,0 +1,195 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+use crate::voxel::{
+    manager::{ChunkManager, PlaceMode},
+    types::{get_block_by_name, Block}
+};
+
+// Marks a cell that leaves the world block untouched
+const VOID_CHAR: char = '.';
+// Symbols handed out to blocks when writing a schematic, in order. No letters, so a row
+// never reads back as a directive, and no '/', so a row never starts a comment
+const KEY_CHARS: &str = "#=+*%&@~:;-!$^_|<>?()[]{},'`0123456789";
+
+/// Pre-built structure stored as a dense block grid.
+///
+/// Text format, one directive per line (`//` starts a comment):
+/// `size <x> <y> <z>`, then `key <char> <block name>` for every symbol,
+/// then one `layer` per Y level (bottom to top) holding `z` rows of `x` symbols.
+#[derive(Clone)]
+pub struct Schematic {
+    pub size: UVec3,
+    // None is structure void
+    pub blocks: Vec<Option<Block>>,
+}
+
+impl Schematic {
+    /// Schematic of the given size filled with structure void
+    pub fn new(size: UVec3) -> Self {
+        Self {
+            size,
+            blocks: vec![None; (size.x * size.y * size.z) as usize],
+        }
+    }
+
+    #[inline]
+    fn index(&self, x: u32, y: u32, z: u32) -> usize {
+        (x + self.size.x * (y + self.size.y * z)) as usize
+    }
+
+    pub fn get(&self, x: u32, y: u32, z: u32) -> Option<Block> {
+        self.blocks[self.index(x, y, z)]
+    }
+
+    pub fn set(&mut self, x: u32, y: u32, z: u32, block: Option<Block>) {
+        let idx = self.index(x, y, z);
+        self.blocks[idx] = block;
+    }
+
+    /// Serializes to the text format `parse` reads
+    pub fn to_text(&self) -> Result<String, String> {
+        let mut keys: Vec<(char, Block)> = Vec::new();
+        let mut symbols = KEY_CHARS.chars();
+
+        let mut layers = String::new();
+        for y in 0..self.size.y {
+            layers.push_str("layer\n");
+            for z in 0..self.size.z {
+                for x in 0..self.size.x {
+                    let symbol = match self.get(x, y, z) {
+                        None => VOID_CHAR,
+                        Some(block) => match keys.iter().find(|(_, b)| b.id == block.id) {
+                            Some((symbol, _)) => *symbol,
+                            None => {
+                                let symbol = symbols.next().ok_or("too many distinct blocks")?;
+                                keys.push((symbol, block));
+                                symbol
+                            }
+                        },
+                    };
+                    layers.push(symbol);
+                }
+                layers.push('\n');
+            }
+        }
+
+        let mut text = format!("size {} {} {}\n", self.size.x, self.size.y, self.size.z);
+        for (symbol, block) in keys {
+            text.push_str(&format!("key {} {}\n", symbol, block.name));
+        }
+        text.push_str(&layers);
+        Ok(text)
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut size: Option<UVec3> = None;
+        let mut keys: HashMap<char, Block> = HashMap::new();
+        let mut layers: Vec<Vec<&str>> = Vec::new();
+
+        for (line_no, raw) in text.lines().enumerate() {
+            let line = raw.trim();
+            if line.is_empty() || line.starts_with("//") {
+                continue;
+            }
+
+            let mut parts = line.split_whitespace();
+            match parts.next() {
+                Some("size") => {
+                    let dims: Vec<u32> = parts
+                        .map(|p| p.parse::<u32>())
+                        .collect::<Result<_, _>>()
+                        .map_err(|e| format!("line {}: bad size: {}", line_no + 1, e))?;
+                    if dims.len() != 3 || dims.contains(&0) {
+                        return Err(format!("line {}: size needs three non-zero values", line_no + 1));
+                    }
+                    size = Some(UVec3::new(dims[0], dims[1], dims[2]));
+                }
+                Some("key") => {
+                    let symbol = parts.next().and_then(|s| s.chars().next());
+                    let name = parts.collect::<Vec<_>>().join(" ");
+                    let (Some(symbol), Some(block)) = (symbol, get_block_by_name(&name)) else {
+                        return Err(format!("line {}: unknown key '{}'", line_no + 1, line));
+                    };
+                    keys.insert(symbol, block);
+                }
+                Some("layer") => layers.push(Vec::new()),
+                _ => match layers.last_mut() {
+                    Some(layer) => layer.push(line),
+                    None => return Err(format!("line {}: row outside of a layer", line_no + 1)),
+                },
+            }
+        }
+
+        let size = size.ok_or("missing size")?;
+        if layers.len() != size.y as usize {
+            return Err(format!("expected {} layers, found {}", size.y, layers.len()));
+        }
+
+        let mut schematic = Self::new(size);
+
+        for (y, rows) in layers.iter().enumerate() {
+            if rows.len() != size.z as usize {
+                return Err(format!("layer {}: expected {} rows, found {}", y, size.z, rows.len()));
+            }
+
+            for (z, row) in rows.iter().enumerate() {
+                let symbols: Vec<char> = row.chars().collect();
+                if symbols.len() != size.x as usize {
+                    return Err(format!("layer {} row {}: expected {} symbols", y, z, size.x));
+                }
+
+                for (x, symbol) in symbols.into_iter().enumerate() {
+                    let block = match symbol {
+                        VOID_CHAR => None,
+                        s => Some(*keys.get(&s).ok_or(format!("unknown symbol '{}'", s))?),
+                    };
+                    schematic.set(x as u32, y as u32, z as u32, block);
+                }
+            }
+        }
+
+        Ok(schematic)
+    }
+
+    /// Writes the structure with its minimum corner at `origin`, deferring blocks in ungenerated chunks
+    pub fn place(&self, cm: &mut ChunkManager, origin: IVec3) {
+        for z in 0..self.size.z {
+            for y in 0..self.size.y {
+                for x in 0..self.size.x {
+                    if let Some(block) = self.get(x, y, z) {
+                        let pos = origin + UVec3::new(x, y, z).as_ivec3();
+                        cm.place_feature_block(pos, block, PlaceMode::Replace);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::voxel::types::BLOCKS;
+
+    #[test]
+    fn every_block_round_trips_through_text() {
+        // One row per block, every symbol in use
+        let mut schematic = Schematic::new(UVec3::new(4, 1, BLOCKS.len() as u32));
+        for (z, block) in BLOCKS.iter().enumerate() {
+            for x in 0..4 {
+                schematic.set(x, 0, z as u32, Some(*block));
+            }
+        }
+
+        let text = schematic.to_text().unwrap();
+        let rows = text.lines().skip_while(|l| *l != "layer").filter(|l| *l != "layer");
+        assert!(rows.flat_map(str::chars).all(|c| !c.is_alphabetic() && c != '/'));
+        let parsed = Schematic::parse(&text).unwrap();
+        assert_eq!(parsed.size, schematic.size);
+        for (z, block) in BLOCKS.iter().enumerate() {
+            assert_eq!(parsed.get(3, 0, z as u32).map(|b| b.id), Some(block.id));
+        }
+    }
+}
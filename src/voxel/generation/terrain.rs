@@ -0,0 +1,69 @@
+use crate::voxel::{
+    chunk::Chunk,
+    generation::{
+        biome::Biome,
+        noise::{fbm_2d, smoothstep, value_noise_2d},
+        GenerationPass, PassContext
+    },
+    types::{CHUNK_SIZE, STONE}
+};
+
+const SUBSURFACE_DEPTH: i32 = 3;
+
+const BASE_HEIGHT: f32 = 64.0;
+const HILL_SALT: u64 = 0x4177_0001;
+const HILL_SCALE: f32 = 64.0;
+const HILL_OCTAVES: u32 = 3;
+const HILL_AMPLITUDE: f32 = 12.0;
+const MOUNTAIN_SALT: u64 = 0x4D7E_0002;
+const MOUNTAIN_SCALE: f32 = 256.0;
+const MOUNTAIN_AMPLITUDE: f32 = 110.0;
+// Mountain noise below this value leaves the terrain flat
+const MOUNTAIN_START: f32 = 0.55;
+// Columns reaching above this are bare stone
+const STONE_PEAK_HEIGHT: i32 = 120;
+
+/// Height of the top terrain block of a column, before caves are carved
+pub fn surface_height(seed: u64, x: i32, z: i32) -> i32 {
+    let (fx, fz) = (x as f32, z as f32);
+    let hills = fbm_2d(seed ^ HILL_SALT, fx, fz, HILL_SCALE, HILL_OCTAVES) * 2.0 - 1.0;
+
+    let mountain = value_noise_2d(seed ^ MOUNTAIN_SALT, fx, fz, MOUNTAIN_SCALE);
+    let mask = smoothstep(((mountain - MOUNTAIN_START) / (1.0 - MOUNTAIN_START)).clamp(0.0, 1.0));
+
+    (BASE_HEIGHT + hills * HILL_AMPLITUDE + mask * MOUNTAIN_AMPLITUDE) as i32
+}
+
+/// Base layers: stone, biome subsurface and biome surface
+pub struct TerrainPass {
+    // Biomes use the world seed so features agree with the terrain they sit on
+    pub seed: u64,
+}
+
+impl GenerationPass for TerrainPass {
+    fn generate(&self, chunk: &mut Chunk, ctx: &PassContext) {
+        let origin = chunk.world_origin();
+
+        for z in 0..CHUNK_SIZE {
+            for x in 0..CHUNK_SIZE {
+                let biome = Biome::at(self.seed, origin.x + x as i32, origin.z + z as i32);
+                let surface = ctx.surface(x, z);
+                let bare = surface > STONE_PEAK_HEIGHT;
+
+                for y in 0..CHUNK_SIZE {
+                    let world_y = origin.y + y as i32;
+                    let block = if world_y > surface {
+                        break;
+                    } else if bare || world_y < surface - SUBSURFACE_DEPTH {
+                        STONE
+                    } else if world_y < surface {
+                        biome.subsurface_block()
+                    } else {
+                        biome.surface_block()
+                    };
+                    chunk.set_block(x, y, z, block);
+                }
+            }
+        }
+    }
+}
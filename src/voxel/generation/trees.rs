@@ -0,0 +1,79 @@
+use bevy::prelude::*;
+
+use crate::voxel::{
+    manager::{ChunkManager, PlaceMode},
+    random::WorldRng,
+    types::{LEAVES, WOOD}
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TreeKind {
+    // Short trunk with a rounded canopy
+    Oak,
+    // Tall trunk with alternating conical layers
+    Spruce,
+    // Single log buried in a small leaf bush
+    Shrub,
+}
+
+/// Places a tree whose trunk starts at `base`
+pub fn place_tree(cm: &mut ChunkManager, base: IVec3, kind: TreeKind, rng: &mut WorldRng) {
+    match kind {
+        TreeKind::Oak => place_oak(cm, base, rng),
+        TreeKind::Spruce => place_spruce(cm, base, rng),
+        TreeKind::Shrub => place_shrub(cm, base, rng),
+    }
+}
+
+fn place_trunk(cm: &mut ChunkManager, base: IVec3, height: i32) {
+    for y in 0..height {
+        cm.place_feature_block(base + IVec3::Y * y, WOOD, PlaceMode::Replace);
+    }
+}
+
+// Horizontal disc of leaves; corners are randomly trimmed so canopies don't look boxy
+fn place_leaf_layer(cm: &mut ChunkManager, center: IVec3, radius: i32, rng: &mut WorldRng) {
+    for dz in -radius..=radius {
+        for dx in -radius..=radius {
+            let corner = dx.abs() == radius && dz.abs() == radius;
+            if radius > 0 && corner && rng.next_f32() < 0.5 {
+                continue;
+            }
+            cm.place_feature_block(center + IVec3::new(dx, 0, dz), LEAVES, PlaceMode::IfAir);
+        }
+    }
+}
+
+fn place_oak(cm: &mut ChunkManager, base: IVec3, rng: &mut WorldRng) {
+    let height = rng.range_i32(4, 6);
+    let top = base + IVec3::Y * (height - 1);
+
+    for dy in -2..=1 {
+        let radius = if dy < 0 { 2 } else { 1 };
+        place_leaf_layer(cm, top + IVec3::Y * dy, radius, rng);
+    }
+    place_trunk(cm, base, height);
+}
+
+fn place_spruce(cm: &mut ChunkManager, base: IVec3, rng: &mut WorldRng) {
+    let height = rng.range_i32(6, 9);
+    let top = base.y + height - 1;
+
+    // Layers alternate between radius 1 and 2 down from a single leaf at the tip
+    for y in (base.y + 2)..=(top + 1) {
+        let from_tip = top + 1 - y;
+        let radius = match from_tip {
+            0 => 0,
+            d if d % 2 == 1 => 1,
+            _ => 2,
+        };
+        place_leaf_layer(cm, IVec3::new(base.x, y, base.z), radius, rng);
+    }
+    place_trunk(cm, base, height);
+}
+
+fn place_shrub(cm: &mut ChunkManager, base: IVec3, rng: &mut WorldRng) {
+    place_leaf_layer(cm, base, 2, rng);
+    place_leaf_layer(cm, base + IVec3::Y, 1, rng);
+    place_trunk(cm, base, 1);
+}
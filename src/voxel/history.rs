@@ -0,0 +1,110 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use crate::player::gamemode::GameMode;
+use crate::voxel::{
+    change::{BlockChangeCause, BlockChanged},
+    manager::ChunkManager,
+    types::Block
+};
+
+// Oldest transactions are dropped past this
+const MAX_TRANSACTIONS: usize = 100;
+
+#[derive(Clone, Copy)]
+struct EditedBlock {
+    pos: IVec3,
+    old: Block,
+    new: Block,
+}
+
+/// Player and building tool edits grouped into transactions; everything changed in one frame is undone together
+#[derive(Resource, Default)]
+pub struct EditHistory {
+    undo: VecDeque<Vec<EditedBlock>>,
+    redo: Vec<Vec<EditedBlock>>,
+}
+
+impl EditHistory {
+    fn record(&mut self, transaction: Vec<EditedBlock>) {
+        if transaction.is_empty() {
+            return;
+        }
+
+        self.undo.push_back(transaction);
+        if self.undo.len() > MAX_TRANSACTIONS {
+            self.undo.pop_front();
+        }
+        // A new edit starts a new branch of history
+        self.redo.clear();
+    }
+
+    /// Reverts the latest transaction, returning how many blocks it touched
+    pub fn undo(&mut self, cm: &mut ChunkManager) -> Option<usize> {
+        let transaction = self.undo.pop_back()?;
+        for edit in transaction.iter().rev() {
+            cm.set_block_world(edit.pos, edit.old, BlockChangeCause::Undo);
+        }
+
+        let count = transaction.len();
+        self.redo.push(transaction);
+        Some(count)
+    }
+
+    /// Reapplies the latest undone transaction, returning how many blocks it touched
+    pub fn redo(&mut self, cm: &mut ChunkManager) -> Option<usize> {
+        let transaction = self.redo.pop()?;
+        for edit in transaction.iter() {
+            cm.set_block_world(edit.pos, edit.new, BlockChangeCause::Redo);
+        }
+
+        let count = transaction.len();
+        self.undo.push_back(transaction);
+        Some(count)
+    }
+}
+
+/// Records console edits, and player edits only in creative: undoing a block mined in
+/// survival would put it back while its drop stays in the inventory
+pub fn record_edit_history(
+    mode: Res<State<GameMode>>,
+    mut history: ResMut<EditHistory>,
+    mut events: EventReader<BlockChanged>,
+) {
+    let creative = *mode.get() == GameMode::Creative;
+    let transaction = events.read()
+        .filter(|change| match change.cause {
+            BlockChangeCause::Edit => true,
+            BlockChangeCause::Player => creative,
+            _ => false,
+        })
+        .map(|change| EditedBlock { pos: change.pos, old: change.old, new: change.new })
+        .collect();
+    history.record(transaction);
+}
+
+/// Right Ctrl+Z undoes, Right Ctrl+Y or Right Ctrl+Shift+Z redoes.
+/// Left Ctrl is the sprint key, so it never starts a shortcut
+pub fn undo_redo_edits(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut history: ResMut<EditHistory>,
+    mut cm: ResMut<ChunkManager>,
+) {
+    if !keys.pressed(KeyCode::ControlRight) {
+        return;
+    }
+    let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+
+    if keys.just_pressed(KeyCode::KeyY) || (shift && keys.just_pressed(KeyCode::KeyZ)) {
+        match history.redo(&mut cm) {
+            Some(count) => info!("↪️ Redid {} block changes", count),
+            None => info!("↪️ Nothing to redo"),
+        }
+    } else if keys.just_pressed(KeyCode::KeyZ) {
+        match history.undo(&mut cm) {
+            Some(count) => info!("↩️ Undid {} block changes", count),
+            None => info!("↩️ Nothing to undo"),
+        }
+    }
+}
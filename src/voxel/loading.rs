@@ -0,0 +1,97 @@
+use bevy::prelude::*;
+
+use crate::player::camera::PlayerCamera;
+use crate::voxel::{
+    generation::WorldGenerator,
+    lod::{chunk_centre, lod_scale_for_distance},
+    manager::ChunkManager,
+    render::{spawn_chunk_mesh, ChunkMaterialHandle, ChunkMeshCache}
+};
+
+// Chunks loaded around the camera in each horizontal direction
+pub const LOAD_RADIUS: i32 = 6;
+// Chunk layers loaded above and below the camera
+pub const VERTICAL_LOAD_RADIUS: i32 = 4;
+// World spans chunk layers 0..WORLD_HEIGHT_CHUNKS (256 blocks)
+pub const WORLD_HEIGHT_CHUNKS: i32 = 16;
+// Lowest block Y of the world, below it is the void
+pub const WORLD_BOTTOM: i32 = 0;
+// Chunks this many chunks past the load area are unloaded again, so walking
+// back and forth along its edge doesn't regenerate them every time
+const UNLOAD_MARGIN: i32 = 2;
+// Generation budget so loading never stalls a frame for long
+const CHUNKS_PER_FRAME: usize = 4;
+// Vertical distance counts for more than horizontal, so the layers at the
+// camera's height fill in before far-off sky and bedrock
+const VERTICAL_PRIORITY_WEIGHT: i32 = 2;
+
+/// Unloads chunks left far behind the camera, and the feature writes still waiting for chunks out there
+pub fn unload_distant_chunks(
+    mut commands: Commands,
+    mut cm: ResMut<ChunkManager>,
+    camera: Query<&Transform, With<PlayerCamera>>,
+) {
+    let Ok(camera) = camera.single() else { return };
+    let (center, _) = ChunkManager::world_to_local(camera.translation.floor().as_ivec3());
+    let distant = |position: IVec3| {
+        let d = (position - center).abs();
+        d.x.max(d.z) > LOAD_RADIUS + UNLOAD_MARGIN || d.y > VERTICAL_LOAD_RADIUS + UNLOAD_MARGIN
+    };
+
+    let unloaded: Vec<IVec3> = cm.chunks.keys().copied().filter(|p| distant(*p)).collect();
+    for position in unloaded {
+        if let Some(entity) = cm.unload_chunk(position) {
+            commands.entity(entity).despawn();
+        }
+    }
+    cm.retain_pending(|p| !distant(p));
+}
+
+pub fn load_chunks_around_camera(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut cache: ResMut<ChunkMeshCache>,
+    mut cm: ResMut<ChunkManager>,
+    generator: Res<WorldGenerator>,
+    material: Res<ChunkMaterialHandle>,
+    camera: Query<&Transform, With<PlayerCamera>>,
+) {
+    let Ok(camera) = camera.single() else { return };
+    let (center, _) = ChunkManager::world_to_local(camera.translation.floor().as_ivec3());
+
+    let mut missing = Vec::new();
+    for dy in -VERTICAL_LOAD_RADIUS..=VERTICAL_LOAD_RADIUS {
+        let y = center.y + dy;
+        if !(0..WORLD_HEIGHT_CHUNKS).contains(&y) {
+            continue;
+        }
+
+        for dz in -LOAD_RADIUS..=LOAD_RADIUS {
+            for dx in -LOAD_RADIUS..=LOAD_RADIUS {
+                let position = IVec3::new(center.x + dx, y, center.z + dz);
+                if cm.get_chunk(position).is_none() {
+                    missing.push(position);
+                }
+            }
+        }
+    }
+
+    missing.sort_by_key(|p| {
+        let d = *p - center;
+        d.y.abs() * VERTICAL_PRIORITY_WEIGHT + d.x.abs().max(d.z.abs())
+    });
+
+    for position in missing.into_iter().take(CHUNKS_PER_FRAME) {
+        generator.generate_chunk(&mut cm, position);
+        let Some(chunk) = cm.get_chunk(position) else { continue };
+
+        let lod_scale = lod_scale_for_distance(camera.translation.distance(chunk_centre(position)));
+        if let Some(entity) = spawn_chunk_mesh(&mut commands, &mut meshes, &mut cache, &material, chunk, lod_scale) {
+            cm.set_chunk_entity(position, entity);
+        }
+        // Freshly meshed; generation writes don't need another pass
+        if let Some(chunk) = cm.get_chunk_mut(position) {
+            chunk.mark_clean();
+        }
+    }
+}
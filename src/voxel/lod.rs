@@ -0,0 +1,71 @@
+use bevy::prelude::*;
+
+use crate::player::camera::PlayerCamera;
+use crate::voxel::{
+    manager::ChunkManager,
+    render::ChunkMeshCache,
+    types::CHUNK_SIZE
+};
+
+// Camera distance (in blocks, to the chunk centre) beyond which each coarser level kicks in
+const LOD_DISTANCES: [(f32, usize); 3] = [(48.0, 2), (96.0, 4), (144.0, 8)];
+pub const MAX_LOD_SCALE: usize = 8;
+// Extra distance needed before switching, so chunks on a boundary don't flicker between levels
+const LOD_HYSTERESIS: f32 = 8.0;
+const LOD_REMESH_PER_FRAME: usize = 8;
+
+/// Attached to every chunk entity; `scale` is the downsampling factor of its current mesh
+#[derive(Component)]
+pub struct ChunkLod {
+    pub position: IVec3,
+    pub scale: usize,
+}
+
+pub fn chunk_centre(position: IVec3) -> Vec3 {
+    (position.as_vec3() + 0.5) * CHUNK_SIZE as f32
+}
+
+/// Downsampling factor for a chunk at `distance` blocks from the camera
+pub fn lod_scale_for_distance(distance: f32) -> usize {
+    LOD_DISTANCES.iter()
+        .rev()
+        .find(|(min, _)| distance >= *min)
+        .map_or(1, |(_, scale)| *scale)
+}
+
+pub fn update_chunk_lods(
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut cache: ResMut<ChunkMeshCache>,
+    cm: Res<ChunkManager>,
+    camera: Query<&Transform, With<PlayerCamera>>,
+    mut chunks: Query<(&mut ChunkLod, &mut Mesh3d)>,
+) {
+    let Ok(camera) = camera.single() else { return };
+    let mut remeshed = 0;
+
+    for (mut lod, mut mesh) in chunks.iter_mut() {
+        if remeshed >= LOD_REMESH_PER_FRAME {
+            break;
+        }
+
+        let distance = camera.translation.distance(chunk_centre(lod.position));
+        let target = lod_scale_for_distance(distance);
+        if target == lod.scale {
+            continue;
+        }
+
+        // Only switch once the camera is clearly past the boundary in either direction
+        let coarser = lod_scale_for_distance(distance - LOD_HYSTERESIS);
+        let finer = lod_scale_for_distance(distance + LOD_HYSTERESIS);
+        if (target > lod.scale && coarser == lod.scale) || (target < lod.scale && finer == lod.scale) {
+            continue;
+        }
+
+        let Some(chunk) = cm.get_chunk(lod.position) else { continue };
+        // The old mesh is freed with its last handle; it may still be shared by other chunks.
+        // A default handle points at no asset, so a chunk with no faces at this level draws nothing
+        mesh.0 = cache.mesh_for_chunk(&mut meshes, chunk, target).unwrap_or_default();
+        lod.scale = target;
+        remeshed += 1;
+    }
+}
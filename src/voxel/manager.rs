@@ -1,44 +1,389 @@
+use std::collections::HashMap;
+
 use bevy::prelude::*;
-use crate::voxel::{chunk::Chunk, types::{CHUNK_SIZE, GRASS, DIRT, STONE, SAND, WATER, WOOD, LEAVES, AIR}};
+use crate::voxel::{
+    change::{BlockChangeCause, BlockChanged},
+    chunk::Chunk,
+    culling::ChunkConnectivity,
+    loading::WORLD_HEIGHT_CHUNKS,
+    types::{Block, CHUNK_SIZE, AIR}
+};
+
+// Height of a column with no blocks loaded in it
+const NO_HEIGHT: i32 = i32::MIN;
+
+/// How a generated feature block treats what is already in the world
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum PlaceMode {
+    Replace,
+    IfAir,
+}
+
+// A feature write into a chunk that hasn't been generated yet
+#[derive(Clone, Copy)]
+struct PendingWrite {
+    local: UVec3,
+    block: Block,
+    mode: PlaceMode,
+}
+
+impl PendingWrite {
+    fn apply(&self, chunk: &mut Chunk) -> bool {
+        let (x, y, z) = (self.local.x as usize, self.local.y as usize, self.local.z as usize);
+        if self.mode == PlaceMode::IfAir && chunk.get_block(x, y, z).id != AIR.id {
+            return false;
+        }
+        chunk.set_block(x, y, z, self.block);
+        true
+    }
+}
+
+/// World positions in the inclusive box between two corners, x fastest
+pub fn aabb_positions(a: IVec3, b: IVec3) -> impl Iterator<Item = IVec3> {
+    let (min, max) = (a.min(b), a.max(b));
+    (min.z..=max.z).flat_map(move |z| {
+        (min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| IVec3::new(x, y, z)))
+    })
+}
+
+/// World positions within `radius` blocks of `centre`
+pub fn sphere_positions(centre: IVec3, radius: i32) -> impl Iterator<Item = IVec3> {
+    let radius_sq = radius * radius;
+    aabb_positions(centre - IVec3::splat(radius), centre + IVec3::splat(radius))
+        .filter(move |pos| pos.distance_squared(centre) <= radius_sq)
+}
+
+/// World Y of the highest non-air block in every column of a chunk column
+#[derive(Clone)]
+pub struct Heightmap {
+    heights: [i32; CHUNK_SIZE * CHUNK_SIZE],
+}
+
+impl Default for Heightmap {
+    fn default() -> Self {
+        Self { heights: [NO_HEIGHT; CHUNK_SIZE * CHUNK_SIZE] }
+    }
+}
+
+impl Heightmap {
+    #[inline]
+    fn index(x: u32, z: u32) -> usize {
+        x as usize + CHUNK_SIZE * z as usize
+    }
+
+    pub fn get(&self, x: u32, z: u32) -> Option<i32> {
+        let h = self.heights[Self::index(x, z)];
+        (h != NO_HEIGHT).then_some(h)
+    }
+}
 
 #[derive(Resource, Default)]
 pub struct ChunkManager {
-    pub chunks: Vec<Chunk>
+    pub chunks: HashMap<IVec3, Chunk>,
+    // Keyed by chunk (x, z); kept up to date on every block write
+    heightmaps: HashMap<IVec2, Heightmap>,
+    // Cached per chunk, dropped whenever one of its blocks changes
+    connectivity: HashMap<IVec3, ChunkConnectivity>,
+    // Cross-chunk feature writes waiting for their chunk to be generated
+    pending: HashMap<IVec3, Vec<PendingWrite>>,
+    // Render entity of every chunk that has been given a mesh
+    entities: HashMap<IVec3, Entity>,
+    // Block writes not yet sent out as `BlockChanged` events
+    changes: Vec<BlockChanged>
 }
 
 impl ChunkManager {
-    pub fn generate_test_chunk(&mut self) {
-        let mut c = Chunk::new(IVec3::new(0,0,0));
+    /// Splits a world block position into chunk position and chunk-local coordinates
+    pub fn world_to_local(pos: IVec3) -> (IVec3, UVec3) {
+        let size = CHUNK_SIZE as i32;
+        (pos.div_euclid(IVec3::splat(size)), pos.rem_euclid(IVec3::splat(size)).as_uvec3())
+    }
+
+    pub fn get_chunk(&self, position: IVec3) -> Option<&Chunk> {
+        self.chunks.get(&position)
+    }
+
+    pub fn get_chunk_mut(&mut self, position: IVec3) -> Option<&mut Chunk> {
+        self.chunks.get_mut(&position)
+    }
+
+    /// Block at a world position, `None` if its chunk is not loaded
+    pub fn get_block_world(&self, pos: IVec3) -> Option<Block> {
+        let (chunk_pos, local) = Self::world_to_local(pos);
+        self.get_chunk(chunk_pos)
+            .map(|c| c.get_block(local.x as usize, local.y as usize, local.z as usize))
+    }
+
+    /// Sets the block at a world position and returns the previous one, `None` if its chunk is not loaded
+    pub fn set_block_world(&mut self, pos: IVec3, block: Block, cause: BlockChangeCause) -> Option<Block> {
+        let (chunk_pos, local) = Self::world_to_local(pos);
+        let chunk = self.get_chunk_mut(chunk_pos)?;
+        let (x, y, z) = (local.x as usize, local.y as usize, local.z as usize);
+        let old = chunk.get_block(x, y, z);
+        chunk.set_block(x, y, z, block);
+        if old.id != block.id {
+            self.update_height(pos, block);
+            self.connectivity.remove(&chunk_pos);
+            self.mark_border_neighbours_dirty(chunk_pos, local);
+            self.changes.push(BlockChanged { pos, old, new: block, cause });
+        }
+        Some(old)
+    }
+
+    pub fn drain_changes(&mut self) -> Vec<BlockChanged> {
+        std::mem::take(&mut self.changes)
+    }
+
+    /// Every block in the inclusive box between two corners, given in any order.
+    /// Blocks in unloaded chunks come back as `None`
+    pub fn blocks_in_aabb(&self, a: IVec3, b: IVec3) -> impl Iterator<Item = (IVec3, Option<Block>)> + '_ {
+        aabb_positions(a, b).map(|pos| (pos, self.get_block_world(pos)))
+    }
+
+    /// Every block whose centre lies within `radius` blocks of `centre`'s centre
+    #[allow(dead_code)]
+    pub fn blocks_in_sphere(&self, centre: IVec3, radius: i32) -> impl Iterator<Item = (IVec3, Option<Block>)> + '_ {
+        sphere_positions(centre, radius).map(|pos| (pos, self.get_block_world(pos)))
+    }
+
+    /// Sets every loaded block in the box to `block`, returning how many changed
+    pub fn fill_region(&mut self, a: IVec3, b: IVec3, block: Block, cause: BlockChangeCause) -> usize {
+        aabb_positions(a, b)
+            .filter(|pos| self.set_block_world(*pos, block, cause).is_some_and(|old| old.id != block.id))
+            .count()
+    }
+
+    /// Swaps every loaded `from` block in the box for `to`, returning how many changed
+    pub fn replace_region(
+        &mut self,
+        a: IVec3,
+        b: IVec3,
+        from: Block,
+        to: Block,
+        cause: BlockChangeCause,
+    ) -> usize {
+        aabb_positions(a, b)
+            .filter(|pos| {
+                self.get_block_world(*pos).is_some_and(|old| old.id == from.id && old.id != to.id)
+                    && self.set_block_world(*pos, to, cause).is_some()
+            })
+            .count()
+    }
+
+    // The mesher only reads the chunk itself and always keeps border faces, so a neighbour's
+    // mesh doesn't change yet; border writes still mark it dirty so that culling or AO across
+    // the shared face can be added to the mesher without missing these remeshes
+    fn mark_border_neighbours_dirty(&mut self, chunk_pos: IVec3, local: UVec3) {
+        let last = CHUNK_SIZE as u32 - 1;
+        for axis in 0..3 {
+            let offset = match local[axis] {
+                0 => -1,
+                l if l == last => 1,
+                _ => continue,
+            };
+            let mut neighbour = chunk_pos;
+            neighbour[axis] += offset;
+            if let Some(chunk) = self.get_chunk_mut(neighbour) {
+                chunk.mark_dirty();
+            }
+        }
+    }
+
+    pub fn chunk_entity(&self, position: IVec3) -> Option<Entity> {
+        self.entities.get(&position).copied()
+    }
+
+    pub fn set_chunk_entity(&mut self, position: IVec3, entity: Entity) {
+        self.entities.insert(position, entity);
+    }
 
-        // Fill a more interesting terrain
+    /// Drops a loaded chunk and what is cached for it, returning its render entity to despawn
+    pub fn unload_chunk(&mut self, position: IVec3) -> Option<Entity> {
+        self.chunks.remove(&position)?;
+        self.connectivity.remove(&position);
+        // The column's heights are only needed while one of its layers is loaded
+        if !(0..WORLD_HEIGHT_CHUNKS).any(|y| self.chunks.contains_key(&position.with_y(y))) {
+            self.heightmaps.remove(&position.xz());
+        }
+        self.entities.remove(&position)
+    }
+
+    /// Forgets the deferred feature writes of every chunk `keep` rejects
+    pub fn retain_pending(&mut self, mut keep: impl FnMut(IVec3) -> bool) {
+        self.pending.retain(|position, _| keep(*position));
+    }
+
+    /// Face-to-face connectivity of a loaded chunk, computed on first use after a change
+    pub fn connectivity(&mut self, position: IVec3) -> Option<ChunkConnectivity> {
+        if let Some(connectivity) = self.connectivity.get(&position) {
+            return Some(*connectivity);
+        }
+
+        let connectivity = ChunkConnectivity::compute(self.chunks.get(&position)?);
+        self.connectivity.insert(position, connectivity);
+        Some(connectivity)
+    }
+
+    /// Highest loaded non-air block in the column containing `(x, z)`
+    pub fn surface_height(&self, x: i32, z: i32) -> Option<i32> {
+        let (chunk_pos, local) = Self::world_to_local(IVec3::new(x, 0, z));
+        self.heightmaps.get(&chunk_pos.xz())?.get(local.x, local.z)
+    }
+
+    /// True when nothing loaded sits above `pos` in its column
+    pub fn is_sky_exposed(&self, pos: IVec3) -> bool {
+        self.surface_height(pos.x, pos.z).is_none_or(|h| pos.y >= h)
+    }
+
+    fn update_height(&mut self, pos: IVec3, block: Block) {
+        let (chunk_pos, local) = Self::world_to_local(pos);
+        let current = self.surface_height(pos.x, pos.z);
+
+        let new_height = if block.id != AIR.id {
+            if current.is_some_and(|h| h >= pos.y) {
+                return;
+            }
+            pos.y
+        } else if current == Some(pos.y) {
+            // The top block was removed, walk down to the next one
+            self.scan_column_down(pos.x, pos.y - 1, pos.z)
+        } else {
+            return;
+        };
+
+        let heightmap = self.heightmaps.entry(chunk_pos.xz()).or_default();
+        heightmap.heights[Heightmap::index(local.x, local.z)] = new_height;
+    }
+
+    fn scan_column_down(&self, x: i32, from_y: i32, z: i32) -> i32 {
+        let mut y = from_y;
+        while let Some(block) = self.get_block_world(IVec3::new(x, y, z)) {
+            if block.id != AIR.id {
+                return y;
+            }
+            y -= 1;
+        }
+        NO_HEIGHT
+    }
+
+    /// Adds a freshly generated chunk, applying any feature writes deferred for it
+    pub fn insert_chunk(&mut self, mut chunk: Chunk) {
+        if let Some(writes) = self.pending.remove(&chunk.position) {
+            for write in writes {
+                write.apply(&mut chunk);
+            }
+        }
+
+        let origin = chunk.world_origin();
+        let heightmap = self.heightmaps.entry(chunk.position.xz()).or_default();
         for z in 0..CHUNK_SIZE {
-            for y in 0..CHUNK_SIZE {
-                for x in 0..CHUNK_SIZE {
-                    if y < 2 {
-                        c.set_block(x, y, z, STONE);
-                    } else if y < 4 {
-                        c.set_block(x, y, z, DIRT);
-                    } else if y == 4 {
-                        c.set_block(x, y, z, GRASS);
-                    } else if y == 5 && x > 8 && x < 12 && z > 8 && z < 12 {
-                        // Add some trees
-                        c.set_block(x, y, z, WOOD);
-                    } else if y == 6 && x > 7 && x < 13 && z > 7 && z < 13 {
-                        // Tree leaves
-                        c.set_block(x, y, z, LEAVES);
-                    } else if y == 4 && x > 2 && x < 6 && z > 2 && z < 6 {
-                        // Add some sand
-                        c.set_block(x, y, z, SAND);
-                    } else if y == 3 && x > 2 && x < 6 && z > 2 && z < 6 {
-                        // Water under sand
-                        c.set_block(x, y, z, WATER);
-                    } else {
-                        c.set_block(x, y, z, AIR);
-                    }
+            for x in 0..CHUNK_SIZE {
+                let top = (0..CHUNK_SIZE).rev().find(|&y| chunk.get_block(x, y, z).id != AIR.id);
+                if let Some(y) = top {
+                    let height = &mut heightmap.heights[Heightmap::index(x as u32, z as u32)];
+                    *height = (*height).max(origin.y + y as i32);
                 }
             }
         }
 
-        self.chunks.push(c);
+        self.connectivity.remove(&chunk.position);
+        self.chunks.insert(chunk.position, chunk);
     }
-}
\ No newline at end of file
+
+    /// Writes a structure block, deferring it if its chunk has not been generated yet
+    pub fn place_feature_block(&mut self, pos: IVec3, block: Block, mode: PlaceMode) {
+        let (chunk_pos, local) = Self::world_to_local(pos);
+        let write = PendingWrite { local, block, mode };
+
+        match self.get_chunk_mut(chunk_pos) {
+            Some(chunk) => {
+                if write.apply(chunk) {
+                    self.update_height(pos, block);
+                    self.connectivity.remove(&chunk_pos);
+                }
+            }
+            None => self.pending.entry(chunk_pos).or_default().push(write),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::voxel::types::{DIRT, STONE};
+
+    // A manager with the chunk at the origin and the one below it loaded
+    fn loaded() -> ChunkManager {
+        let mut cm = ChunkManager::default();
+        cm.insert_chunk(Chunk::new(IVec3::ZERO));
+        cm.insert_chunk(Chunk::new(IVec3::NEG_Y));
+        cm
+    }
+
+    #[test]
+    fn world_to_local_rounds_negative_positions_down() {
+        assert_eq!(ChunkManager::world_to_local(IVec3::new(-1, 0, 0)), (IVec3::new(-1, 0, 0), UVec3::new(15, 0, 0)));
+        assert_eq!(ChunkManager::world_to_local(IVec3::new(-16, -17, 16)), (IVec3::new(-1, -2, 1), UVec3::new(0, 15, 0)));
+        assert_eq!(ChunkManager::world_to_local(IVec3::new(15, 31, 0)), (IVec3::new(0, 1, 0), UVec3::new(15, 15, 0)));
+    }
+
+    #[test]
+    fn unloaded_chunks_read_and_write_as_none() {
+        let mut cm = loaded();
+        assert_eq!(cm.get_block_world(IVec3::new(0, -1, 0)).map(|b| b.id), Some(AIR.id));
+        assert!(cm.get_block_world(IVec3::new(16, 0, 0)).is_none());
+        assert!(cm.set_block_world(IVec3::new(-1, 0, 0), STONE, BlockChangeCause::Player).is_none());
+        assert!(cm.drain_changes().is_empty());
+    }
+
+    #[test]
+    fn aabb_covers_the_inclusive_box_in_any_corner_order() {
+        let cm = loaded();
+        assert_eq!(cm.blocks_in_aabb(IVec3::new(2, 3, 4), IVec3::ZERO).count(), 3 * 4 * 5);
+        // Half of this box sits in unloaded chunks at x < 0
+        let blocks: Vec<_> = cm.blocks_in_aabb(IVec3::new(-2, 0, 0), IVec3::new(1, 1, 1)).collect();
+        assert_eq!(blocks.len(), 4 * 2 * 2);
+        assert_eq!(blocks.iter().filter(|(_, b)| b.is_none()).count(), 2 * 2 * 2);
+    }
+
+    #[test]
+    fn sphere_keeps_blocks_within_the_radius() {
+        let cm = loaded();
+        assert_eq!(cm.blocks_in_sphere(IVec3::new(8, 8, 8), 0).count(), 1);
+        assert_eq!(cm.blocks_in_sphere(IVec3::new(8, 8, 8), 1).count(), 7);
+        assert_eq!(cm.blocks_in_sphere(IVec3::new(8, 8, 8), 2).count(), 33);
+        assert!(cm.blocks_in_sphere(IVec3::new(8, 8, 8), 3).all(|(pos, _)| pos.distance_squared(IVec3::splat(8)) <= 9));
+    }
+
+    #[test]
+    fn fill_and_replace_count_only_changed_loaded_blocks() {
+        let mut cm = loaded();
+        // 4 x 2 x 2 box, the x < 0 half is unloaded
+        assert_eq!(cm.fill_region(IVec3::new(-2, 0, 0), IVec3::new(1, 1, 1), STONE, BlockChangeCause::Player), 8);
+        assert_eq!(cm.fill_region(IVec3::ZERO, IVec3::new(1, 1, 1), STONE, BlockChangeCause::Player), 0);
+        assert_eq!(cm.fill_region(IVec3::ZERO, IVec3::new(0, 0, 1), DIRT, BlockChangeCause::Player), 2);
+
+        assert_eq!(cm.replace_region(IVec3::ZERO, IVec3::new(3, 3, 3), STONE, DIRT, BlockChangeCause::Player), 6);
+        assert_eq!(cm.replace_region(IVec3::ZERO, IVec3::new(3, 3, 3), STONE, DIRT, BlockChangeCause::Player), 0);
+        assert_eq!(cm.replace_region(IVec3::ZERO, IVec3::new(3, 3, 3), DIRT, DIRT, BlockChangeCause::Player), 0);
+        assert_eq!(cm.drain_changes().len(), 8 + 2 + 6);
+    }
+
+    #[test]
+    fn unloading_forgets_the_chunk_its_column_and_dropped_writes() {
+        let mut cm = loaded();
+        cm.set_block_world(IVec3::new(1, 5, 1), STONE, BlockChangeCause::Player);
+        cm.set_chunk_entity(IVec3::ZERO, Entity::PLACEHOLDER);
+
+        assert_eq!(cm.unload_chunk(IVec3::ZERO), Some(Entity::PLACEHOLDER));
+        assert!(cm.unload_chunk(IVec3::ZERO).is_none());
+        assert!(cm.get_block_world(IVec3::new(1, 5, 1)).is_none());
+        cm.unload_chunk(IVec3::NEG_Y);
+        assert_eq!(cm.surface_height(1, 1), None);
+
+        cm.place_feature_block(IVec3::new(2, 2, 2), DIRT, PlaceMode::Replace);
+        cm.retain_pending(|p| p != IVec3::ZERO);
+        cm.insert_chunk(Chunk::new(IVec3::ZERO));
+        assert_eq!(cm.get_block_world(IVec3::new(2, 2, 2)).map(|b| b.id), Some(AIR.id));
+    }
+}
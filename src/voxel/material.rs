@@ -0,0 +1,69 @@
+use bevy::{
+    pbr::{MaterialPipeline, MaterialPipelineKey},
+    prelude::*,
+    render::{
+        mesh::{MeshVertexAttribute, MeshVertexBufferLayoutRef},
+        render_resource::{
+            AsBindGroup, RenderPipelineDescriptor, ShaderRef,
+            SpecializedMeshPipelineError, VertexFormat
+        },
+    },
+};
+
+use crate::voxel::types::BLOCKS;
+
+const SHADER_ASSET_PATH: &str = "shaders/chunk.wgsl";
+
+// Direction sunlight travels in, shared by the chunk shader and the scene light
+pub const SUN_DIRECTION: Vec3 = Vec3::new(-0.4, -1.0, -0.3);
+// Fraction of light faces pointing away from the sun still receive
+pub const AMBIENT_LIGHT: f32 = 0.45;
+
+/// Two u32 per vertex, see `pack_vertex` in the mesher for the bit layout
+pub const ATTRIBUTE_PACKED_VERTEX: MeshVertexAttribute =
+    MeshVertexAttribute::new("Vertex_Packed", 0x6A3E_2C01_9F11_0033, VertexFormat::Uint32x2);
+
+#[derive(Asset, TypePath, AsBindGroup, Clone)]
+pub struct ChunkMaterial {
+    // xyz is the direction light travels in, w is the ambient term
+    #[uniform(0)]
+    pub sun: Vec4,
+    // Linear RGBA per block id, looked up with the vertex texture id
+    #[uniform(1)]
+    pub palette: [Vec4; 256],
+}
+
+impl ChunkMaterial {
+    pub fn new(sun_direction: Vec3, ambient: f32) -> Self {
+        let mut palette = [Vec4::ZERO; 256];
+        for block in BLOCKS {
+            palette[block.id as usize] = block.color.to_linear().to_vec4();
+        }
+
+        Self {
+            sun: sun_direction.normalize().extend(ambient),
+            palette,
+        }
+    }
+}
+
+impl Material for ChunkMaterial {
+    fn vertex_shader() -> ShaderRef {
+        SHADER_ASSET_PATH.into()
+    }
+
+    fn fragment_shader() -> ShaderRef {
+        SHADER_ASSET_PATH.into()
+    }
+
+    fn specialize(
+        _pipeline: &MaterialPipeline<Self>,
+        descriptor: &mut RenderPipelineDescriptor,
+        layout: &MeshVertexBufferLayoutRef,
+        _key: MaterialPipelineKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        let vertex_layout = layout.0.get_layout(&[ATTRIBUTE_PACKED_VERTEX.at_shader_location(0)])?;
+        descriptor.vertex.buffers = vec![vertex_layout];
+        Ok(())
+    }
+}
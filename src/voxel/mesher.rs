@@ -4,188 +4,317 @@ use bevy::{
 
 use crate::voxel::{
     chunk::Chunk,
-    types::{CHUNK_SIZE, CHUNK_VOLUME, AIR}
+    material::ATTRIBUTE_PACKED_VERTEX,
+    types::{Block, CHUNK_SIZE, CHUNK_VOLUME, AIR}
 };
 
+// Cells a LOD skirt hangs below the surface along chunk borders
+pub const SKIRT_CELLS: i32 = 2;
+// Added to packed positions so skirts reaching below the chunk stay unsigned.
+// Must match POSITION_BIAS in assets/shaders/chunk.wgsl
+const POSITION_BIAS: i32 = 8;
+// No lighting engine yet, every face is fully lit
+const FULL_LIGHT: u32 = 15;
+// Corner with no occluding neighbours
+const NO_OCCLUSION: u32 = 3;
+
 pub struct ChunkMesh {
     pub mesh: Mesh,
     // pub translation: Vec3
 }
 
-// Ultra-optimized color conversion - converts Bevy Color to RGBA array
+/// Packs a chunk-local vertex into two u32:
+/// word 0 holds x, y, z (8 bits each, biased), normal index (3 bits) and ambient occlusion (2 bits);
+/// word 1 holds the texture id (8 bits, the block id) and light level (4 bits)
 #[inline]
-fn color_to_rgba_fast(color: &Color) -> [f32; 4] {
-    let srgba = color.to_srgba();
-    [srgba.red, srgba.green, srgba.blue, srgba.alpha]
+pub fn pack_vertex(pos: IVec3, normal: u32, ao: u32, texture: u8, light: u32) -> [u32; 2] {
+    let biased = pos + IVec3::splat(POSITION_BIAS);
+    // Guards against world offsets creeping back into chunk meshes
+    debug_assert!(
+        biased.min_element() >= 0 && biased.max_element() <= u8::MAX as i32,
+        "vertex {pos} is outside the chunk-local range"
+    );
+    let p = biased.as_uvec3();
+    [
+        p.x | (p.y << 8) | (p.z << 16) | (normal << 24) | (ao << 27),
+        texture as u32 | (light << 8),
+    ]
 }
 
-fn push_quad(
-    positions: &mut Vec<[f32; 3]>,
-    normals: &mut Vec<[f32; 3]>,
-    uvs: &mut Vec<[f32; 2]>,
-    indices: &mut Vec<u32>,
-    colors: &mut Vec<[f32; 4]>,
-    base_index: u32,
-    a: [f32; 3],
-    b: [f32; 3],
-    c: [f32; 3],
-    d: [f32; 3],
-    normal: [f32; 3],
-    block_color: [f32; 4],
-) {
-    positions.push(a);
-    positions.push(b);
-    positions.push(c);
-    positions.push(d);
-
-    normals.push(normal);
-    normals.push(normal);
-    normals.push(normal);
-    normals.push(normal);
-
-    // UV coordinates for each vertex
-    uvs.push([0.0, 0.0]);
-    uvs.push([1.0, 0.0]);
-    uvs.push([1.0, 1.0]);
-    uvs.push([0.0, 1.0]);
-
-    // Add colors for all 4 vertices of this quad
-    colors.push(block_color);
-    colors.push(block_color);
-    colors.push(block_color);
-    colors.push(block_color);
-
-    // Correct winding order for CCW (counter-clockwise) when viewed from outside
-    // First triangle: a -> b -> c
-    indices.push(base_index + 0);
-    indices.push(base_index + 1);
-    indices.push(base_index + 2);
-
-    // Second triangle: a -> c -> d
-    indices.push(base_index + 0);
-    indices.push(base_index + 2);
-    indices.push(base_index + 3);
+/// A vertex read back from its packed form, for tools that consume mesher output
+pub struct UnpackedVertex {
+    pub position: IVec3,
+    pub normal: IVec3,
+    pub ao: u32,
+    pub texture: u8,
+    pub light: u32,
 }
 
-fn should_render_face(chunk: &Chunk, x: i32, y: i32, z: i32) -> bool {
-    // Check bounds first
-    if x < 0 || y < 0 || z < 0 || 
-       x >= CHUNK_SIZE as i32 || y >= CHUNK_SIZE as i32 || z >= CHUNK_SIZE as i32 {
-        return true; // Render face at chunk boundary
+pub fn unpack_vertex(packed: [u32; 2]) -> UnpackedVertex {
+    let [word0, word1] = packed;
+    let position = IVec3::new(
+        (word0 & 0xFF) as i32,
+        ((word0 >> 8) & 0xFF) as i32,
+        ((word0 >> 16) & 0xFF) as i32,
+    ) - IVec3::splat(POSITION_BIAS);
+
+    UnpackedVertex {
+        position,
+        normal: FACES[((word0 >> 24) & 0x7) as usize].dir,
+        ao: (word0 >> 27) & 0x3,
+        texture: (word1 & 0xFF) as u8,
+        light: (word1 >> 8) & 0xF,
     }
-    
-    // Check if adjacent block is air
-    chunk.get_block(x as usize, y as usize, z as usize).id == AIR.id
 }
 
-pub fn generate_mesh_for_chunk(chunk: &Chunk) -> ChunkMesh {
-    let mut positions: Vec<[f32; 3]> = Vec::new();
-    let mut normals: Vec<[f32; 3]> = Vec::new();
-    let mut uvs: Vec<[f32; 2]> = Vec::new();
-    let mut indices: Vec<u32> = Vec::new();
-    let mut colors: Vec<[f32; 4]> = Vec::new();
-
-    // Pre-allocate vectors for better performance
-    positions.reserve(CHUNK_VOLUME * 6 * 4); // 6 faces * 4 vertices per face, worst case
-    normals.reserve(CHUNK_VOLUME * 6 * 4);
-    uvs.reserve(CHUNK_VOLUME * 6 * 4);
-    colors.reserve(CHUNK_VOLUME * 6 * 4);
-    indices.reserve(CHUNK_VOLUME * 6 * 6); // 6 faces * 6 indices per face
-
-    for z in 0..CHUNK_SIZE {
-        for y in 0..CHUNK_SIZE {
-            for x in 0..CHUNK_SIZE {
-                let block = chunk.get_block(x, y, z);
-                if block.id == AIR.id { continue; }
+struct Face {
+    // Direction of the neighbour that hides this face
+    dir: IVec3,
+    // Corners on the unit cube, CCW (counter-clockwise) when viewed from outside
+    corners: [IVec3; 4],
+}
 
-                let world_x = x as f32 + (chunk.position.x as f32 * CHUNK_SIZE as f32);
-                let world_y = y as f32 + (chunk.position.y as f32 * CHUNK_SIZE as f32);
-                let world_z = z as f32 + (chunk.position.z as f32 * CHUNK_SIZE as f32);
-
-                let block_color = color_to_rgba_fast(&block.color);
-
-                // Right face (+X) - vista desde afuera del cubo
-                if should_render_face(chunk, x as i32 + 1, y as i32, z as i32) {
-                    let base_index = positions.len() as u32;
-                    let a = [world_x + 1.0, world_y,       world_z + 1.0]; // bottom-right
-                    let b = [world_x + 1.0, world_y,       world_z      ]; // bottom-left  
-                    let c = [world_x + 1.0, world_y + 1.0, world_z      ]; // top-left
-                    let d = [world_x + 1.0, world_y + 1.0, world_z + 1.0]; // top-right
-                    let normal = [1.0, 0.0, 0.0];
-                    push_quad(&mut positions, &mut normals, &mut uvs, &mut indices, &mut colors, 
-                             base_index, a, b, c, d, normal, block_color);
-                }
-                
-                // Left face (-X) - vista desde afuera del cubo
-                if should_render_face(chunk, x as i32 - 1, y as i32, z as i32) {
-                    let base_index = positions.len() as u32;
-                    let a = [world_x, world_y,       world_z      ]; // bottom-right
-                    let b = [world_x, world_y,       world_z + 1.0]; // bottom-left
-                    let c = [world_x, world_y + 1.0, world_z + 1.0]; // top-left  
-                    let d = [world_x, world_y + 1.0, world_z      ]; // top-right
-                    let normal = [-1.0, 0.0, 0.0];
-                    push_quad(&mut positions, &mut normals, &mut uvs, &mut indices, &mut colors, 
-                             base_index, a, b, c, d, normal, block_color);
-                }
+// Indexed by the normal index the shader decodes
+const FACES: [Face; 6] = [
+    // Right face (+X)
+    Face { dir: IVec3::X,
+        corners: [IVec3::new(1, 0, 1), IVec3::new(1, 0, 0), IVec3::new(1, 1, 0), IVec3::new(1, 1, 1)] },
+    // Left face (-X)
+    Face { dir: IVec3::NEG_X,
+        corners: [IVec3::new(0, 0, 0), IVec3::new(0, 0, 1), IVec3::new(0, 1, 1), IVec3::new(0, 1, 0)] },
+    // Top face (+Y)
+    Face { dir: IVec3::Y,
+        corners: [IVec3::new(0, 1, 1), IVec3::new(1, 1, 1), IVec3::new(1, 1, 0), IVec3::new(0, 1, 0)] },
+    // Bottom face (-Y)
+    Face { dir: IVec3::NEG_Y,
+        corners: [IVec3::new(0, 0, 0), IVec3::new(1, 0, 0), IVec3::new(1, 0, 1), IVec3::new(0, 0, 1)] },
+    // Front face (+Z)
+    Face { dir: IVec3::Z,
+        corners: [IVec3::new(0, 0, 1), IVec3::new(1, 0, 1), IVec3::new(1, 1, 1), IVec3::new(0, 1, 1)] },
+    // Back face (-Z)
+    Face { dir: IVec3::NEG_Z,
+        corners: [IVec3::new(1, 0, 0), IVec3::new(0, 0, 0), IVec3::new(0, 1, 0), IVec3::new(1, 1, 0)] },
+];
 
-                // Top face (+Y) - vista desde afuera del cubo
-                if should_render_face(chunk, x as i32, y as i32 + 1, z as i32) {
-                    let base_index = positions.len() as u32;
-                    let a = [world_x,       world_y + 1.0, world_z + 1.0]; // bottom-left
-                    let b = [world_x + 1.0, world_y + 1.0, world_z + 1.0]; // bottom-right
-                    let c = [world_x + 1.0, world_y + 1.0, world_z      ]; // top-right
-                    let d = [world_x,       world_y + 1.0, world_z      ]; // top-left
-                    let normal = [0.0, 1.0, 0.0];
-                    push_quad(&mut positions, &mut normals, &mut uvs, &mut indices, &mut colors, 
-                             base_index, a, b, c, d, normal, block_color);
-                }
+#[derive(Default)]
+struct MeshBuffers {
+    vertices: Vec<[u32; 2]>,
+    indices: Vec<u32>,
+}
 
-                // Bottom face (-Y) - vista desde afuera del cubo
-                if should_render_face(chunk, x as i32, y as i32 - 1, z as i32) {
-                    let base_index = positions.len() as u32;
-                    let a = [world_x,       world_y, world_z      ]; // bottom-left
-                    let b = [world_x + 1.0, world_y, world_z      ]; // bottom-right
-                    let c = [world_x + 1.0, world_y, world_z + 1.0]; // top-right
-                    let d = [world_x,       world_y, world_z + 1.0]; // top-left
-                    let normal = [0.0, -1.0, 0.0];
-                    push_quad(&mut positions, &mut normals, &mut uvs, &mut indices, &mut colors, 
-                             base_index, a, b, c, d, normal, block_color);
+impl MeshBuffers {
+    fn with_capacity(quads: usize) -> Self {
+        Self {
+            vertices: Vec::with_capacity(quads * 4),
+            indices: Vec::with_capacity(quads * 6),
+        }
+    }
+
+    // Emits face `normal` of the box spanning `min..min + size`, in chunk-local blocks
+    fn push_face(&mut self, normal: usize, min: IVec3, size: IVec3, block: Block, ao: [u32; 4]) {
+        let base_index = self.vertices.len() as u32;
+
+        for (corner, corner_ao) in FACES[normal].corners.iter().zip(ao) {
+            self.vertices.push(pack_vertex(min + *corner * size, normal as u32, corner_ao, block.id, FULL_LIGHT));
+        }
+
+        // First triangle: a -> b -> c, second triangle: a -> c -> d
+        self.indices.extend_from_slice(&[
+            base_index, base_index + 1, base_index + 2,
+            base_index, base_index + 2, base_index + 3,
+        ]);
+    }
+
+    fn into_mesh(self) -> Mesh {
+        let mut mesh = Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default());
+        mesh.insert_attribute(ATTRIBUTE_PACKED_VERTEX, self.vertices);
+        mesh.insert_indices(Indices::U32(self.indices));
+        mesh
+    }
+}
+
+/// Chunk blocks on a grid of `scale`-sized cells, each cell holding its majority block
+struct CellGrid {
+    size: usize,
+    cells: Vec<Block>,
+}
+
+impl CellGrid {
+    fn full(chunk: &Chunk) -> Self {
+        Self { size: CHUNK_SIZE, cells: chunk.blocks.clone() }
+    }
+
+    fn downsampled(chunk: &Chunk, scale: usize) -> Self {
+        let size = CHUNK_SIZE / scale;
+        let mut cells = Vec::with_capacity(size * size * size);
+        let mut counts = [0u16; 256];
+
+        for cz in 0..size {
+            for cy in 0..size {
+                for cx in 0..size {
+                    counts.fill(0);
+                    let mut winner = AIR;
+
+                    for z in cz * scale..(cz + 1) * scale {
+                        for y in cy * scale..(cy + 1) * scale {
+                            for x in cx * scale..(cx + 1) * scale {
+                                let block = chunk.get_block(x, y, z);
+                                counts[block.id as usize] += 1;
+                                if block.id != AIR.id
+                                    && (winner.id == AIR.id || counts[block.id as usize] > counts[winner.id as usize])
+                                {
+                                    winner = block;
+                                }
+                            }
+                        }
+                    }
+
+                    // Ties go to the solid block so thin terrain doesn't vanish at a distance
+                    if counts[AIR.id as usize] > counts[winner.id as usize] {
+                        winner = AIR;
+                    }
+                    cells.push(winner);
                 }
+            }
+        }
 
-                // Front face (+Z) - vista desde afuera del cubo
-                if should_render_face(chunk, x as i32, y as i32, z as i32 + 1) {
-                    let base_index = positions.len() as u32;
-                    let a = [world_x,       world_y,       world_z + 1.0]; // bottom-left
-                    let b = [world_x + 1.0, world_y,       world_z + 1.0]; // bottom-right
-                    let c = [world_x + 1.0, world_y + 1.0, world_z + 1.0]; // top-right
-                    let d = [world_x,       world_y + 1.0, world_z + 1.0]; // top-left
-                    let normal = [0.0, 0.0, 1.0];
-                    push_quad(&mut positions, &mut normals, &mut uvs, &mut indices, &mut colors, 
-                             base_index, a, b, c, d, normal, block_color);
+        Self { size, cells }
+    }
+
+    #[inline]
+    fn get(&self, x: i32, y: i32, z: i32) -> Option<Block> {
+        let n = self.size as i32;
+        if x < 0 || y < 0 || z < 0 || x >= n || y >= n || z >= n {
+            return None;
+        }
+        Some(self.cells[x as usize + self.size * (y as usize + self.size * z as usize)])
+    }
+}
+
+// Classic voxel AO: each face corner is darkened by the two edge neighbours and the
+// diagonal neighbour in the layer the face looks into
+fn corner_ao(grid: &CellGrid, cell: IVec3, face: &Face, corner: IVec3) -> u32 {
+    let solid = |p: IVec3| grid.get(p.x, p.y, p.z).is_some_and(|b| b.is_solid());
+    let layer = cell + face.dir;
+
+    // The two axes spanning the face, each pointing toward this corner
+    let axis = face.dir.abs();
+    let (u, v) = if axis.x != 0 { (IVec3::Y, IVec3::Z) } else if axis.y != 0 { (IVec3::X, IVec3::Z) } else { (IVec3::X, IVec3::Y) };
+    let su = if (corner * u) != IVec3::ZERO { u } else { -u };
+    let sv = if (corner * v) != IVec3::ZERO { v } else { -v };
+
+    let side1 = solid(layer + su);
+    let side2 = solid(layer + sv);
+    if side1 && side2 {
+        return 0;
+    }
+    NO_OCCLUSION - side1 as u32 - side2 as u32 - solid(layer + su + sv) as u32
+}
+
+/// Meshes a chunk at `1 / scale` resolution; `scale` must divide `CHUNK_SIZE`.
+/// Chunk-border faces are always kept; downsampled meshes also hang skirts below border
+/// surfaces, hiding the cracks against neighbours meshed at a different level.
+pub fn generate_mesh_for_chunk(chunk: &Chunk, scale: usize) -> ChunkMesh {
+    let grid = if scale == 1 { CellGrid::full(chunk) } else { CellGrid::downsampled(chunk, scale) };
+    let n = grid.size as i32;
+    let s = scale as i32;
+    let cell_size = IVec3::splat(s);
+
+    // 6 faces per cell, worst case
+    let mut buffers = MeshBuffers::with_capacity((CHUNK_VOLUME / (scale * scale * scale)) * 6);
+
+    for z in 0..n {
+        for y in 0..n {
+            for x in 0..n {
+                let Some(block) = grid.get(x, y, z) else { continue };
+                if block.id == AIR.id { continue; }
+
+                // Vertices are relative to the chunk, its entity Transform places it in the world
+                let cell = IVec3::new(x, y, z);
+                let min = cell * s;
+
+                for (normal, face) in FACES.iter().enumerate() {
+                    let neighbour = cell + face.dir;
+                    let visible = match grid.get(neighbour.x, neighbour.y, neighbour.z) {
+                        // Check if adjacent block is air
+                        Some(adjacent) => adjacent.id == AIR.id,
+                        // Render face at chunk boundary, at every level
+                        None => true,
+                    };
+                    if visible {
+                        let ao = face.corners.map(|corner| corner_ao(&grid, cell, face, corner));
+                        buffers.push_face(normal, min, cell_size, block, ao);
+                    }
                 }
 
-                // Back face (-Z) - vista desde afuera del cubo
-                if should_render_face(chunk, x as i32, y as i32, z as i32 - 1) {
-                    let base_index = positions.len() as u32;
-                    let a = [world_x + 1.0, world_y,       world_z]; // bottom-left
-                    let b = [world_x,       world_y,       world_z]; // bottom-right
-                    let c = [world_x,       world_y + 1.0, world_z]; // top-right
-                    let d = [world_x + 1.0, world_y + 1.0, world_z]; // top-left
-                    let normal = [0.0, 0.0, -1.0];
-                    push_quad(&mut positions, &mut normals, &mut uvs, &mut indices, &mut colors, 
-                             base_index, a, b, c, d, normal, block_color);
+                // Skirts below border cells that are the top of their column, down to the
+                // first cell whose own border wall already closes the gap
+                let is_surface = grid.get(x, y + 1, z).is_none_or(|above| above.id == AIR.id);
+                if scale > 1 && is_surface {
+                    for (normal, face) in FACES.iter().enumerate().filter(|(_, f)| f.dir.y == 0) {
+                        let neighbour = cell + face.dir;
+                        if grid.get(neighbour.x, neighbour.y, neighbour.z).is_some() {
+                            continue;
+                        }
+                        for depth in 1..SKIRT_CELLS {
+                            let below = cell - IVec3::Y * depth;
+                            if grid.get(below.x, below.y, below.z).is_some_and(|b| b.id != AIR.id) {
+                                break;
+                            }
+                            buffers.push_face(normal, below * s, cell_size, block, [NO_OCCLUSION; 4]);
+                        }
+                    }
                 }
             }
         }
     }
 
-    // Build Mesh - ultra-optimized
-    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default());
-    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
-    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
-    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
-    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
-    mesh.insert_indices(Indices::U32(indices));
+    ChunkMesh { mesh: buffers.into_mesh() }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::render::mesh::VertexAttributeValues;
+
+    use super::*;
+    use crate::voxel::types::STONE;
+
+    #[test]
+    fn vertices_stay_chunk_local_away_from_the_origin() {
+        let mut chunk = Chunk::new(IVec3::new(-3, 2, 7));
+        let last = CHUNK_SIZE - 1;
+        for (x, y, z) in [(0, 0, 0), (last, last, last), (0, last, 0), (7, 8, 9)] {
+            chunk.set_block(x, y, z, STONE);
+        }
+
+        let mesh = generate_mesh_for_chunk(&chunk, 1).mesh;
+        let Some(VertexAttributeValues::Uint32x2(packed)) = mesh.attribute(ATTRIBUTE_PACKED_VERTEX) else {
+            panic!("chunk mesh has no packed vertices");
+        };
+
+        assert!(!packed.is_empty());
+        for vertex in packed {
+            let position = unpack_vertex(*vertex).position;
+            assert!(
+                position.min_element() >= 0 && position.max_element() <= CHUNK_SIZE as i32,
+                "vertex {position} is outside the chunk"
+            );
+        }
+    }
+
+    #[test]
+    fn downsampled_solid_chunks_keep_their_border_faces() {
+        let mut chunk = Chunk::new(IVec3::ZERO);
+        chunk.blocks.fill(STONE);
 
-    ChunkMesh { mesh }
-}
\ No newline at end of file
+        let mesh = generate_mesh_for_chunk(&chunk, 2).mesh;
+        let Some(VertexAttributeValues::Uint32x2(packed)) = mesh.attribute(ATTRIBUTE_PACKED_VERTEX) else {
+            panic!("chunk mesh has no packed vertices");
+        };
+
+        let normals: Vec<IVec3> = packed.iter().map(|v| unpack_vertex(*v).normal).collect();
+        assert!(normals.contains(&IVec3::Y), "solid chunk has no top face");
+        // Only the outer shell: 6 sides of 8 x 8 cells, 4 vertices each, no skirts
+        assert_eq!(packed.len(), 6 * 8 * 8 * 4);
+    }
+}
@@ -1,8 +1,22 @@
+pub mod behavior;
+pub mod change;
 pub mod chunk;
+pub mod culling;
+pub mod export;
+pub mod falling;
+pub mod generation;
+pub mod history;
+pub mod loading;
+pub mod lod;
+pub mod material;
 pub mod manager;
 pub mod mesher;
+pub mod random;
+pub mod raycast;
 pub mod render;
+pub mod tick;
 pub mod types;
+pub mod vox;
 
 pub use plugin::VoxelPlugin;
 mod plugin;
\ No newline at end of file
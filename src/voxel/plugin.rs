@@ -1,28 +1,80 @@
 use bevy::prelude::*;
 
+use crate::player::gamemode::GameMode;
+use crate::voxel::behavior::BlockBehaviors;
+use crate::voxel::change::{emit_block_changes, BlockChanged};
+use crate::voxel::culling::{cull_occluded_chunks, log_culling_stats, ChunkCullingStats};
+use crate::voxel::falling::{
+    setup_falling_block_assets, spawn_falling_blocks, update_falling_blocks, BlockDetached
+};
+use crate::voxel::generation::WorldGenerator;
+use crate::voxel::history::{record_edit_history, undo_redo_edits, EditHistory};
 use crate::voxel::manager::ChunkManager;
-use crate::voxel::render::spawn_chunk_meshes;
+use crate::voxel::random::WorldRng;
+use crate::voxel::loading::{load_chunks_around_camera, unload_distant_chunks};
+use crate::voxel::lod::update_chunk_lods;
+use crate::voxel::material::{ChunkMaterial, SUN_DIRECTION};
+use crate::voxel::render::{
+    prune_chunk_mesh_cache, remesh_dirty_chunks, setup_chunk_material, ChunkMeshCache
+};
+use crate::voxel::tick::{
+    advance_world_tick, notify_external_changes, run_random_ticks, run_scheduled_ticks,
+    ScheduledUpdates, WorldTick, TICKS_PER_SECOND
+};
 
 pub struct VoxelPlugin;
 
 impl Plugin for VoxelPlugin {
     fn build(&self, app: &mut App) {
         app
+            .add_plugins(MaterialPlugin::<ChunkMaterial>::default())
             .insert_resource(ChunkManager::default())
+            .init_resource::<WorldGenerator>()
+            .insert_resource(Time::<Fixed>::from_hz(TICKS_PER_SECOND))
+            .insert_resource(BlockBehaviors::with_defaults())
+            .init_resource::<WorldTick>()
+            .init_resource::<ScheduledUpdates>()
+            .init_resource::<WorldRng>()
+            .init_resource::<ChunkCullingStats>()
+            .init_resource::<ChunkMeshCache>()
+            .init_resource::<EditHistory>()
+            .add_event::<BlockDetached>()
+            .add_event::<BlockChanged>()
             .add_systems(Startup, (
-                spawn_chunk_meshes,
-                spawn_light
-            ));
+                setup_chunk_material,
+                spawn_light,
+                setup_falling_block_assets
+            ))
+            .add_systems(Update, (
+                (unload_distant_chunks, load_chunks_around_camera, remesh_dirty_chunks).chain(),
+                update_chunk_lods,
+                prune_chunk_mesh_cache,
+                cull_occluded_chunks,
+                log_culling_stats,
+                spawn_falling_blocks,
+                update_falling_blocks,
+                (
+                    undo_redo_edits.run_if(in_state(GameMode::Creative)),
+                    emit_block_changes,
+                    (record_edit_history, notify_external_changes)
+                ).chain()
+            ))
+            .add_systems(FixedUpdate, (
+                advance_world_tick,
+                run_scheduled_ticks,
+                run_random_ticks
+            ).chain());
     }
 }
 
 fn spawn_light(mut commands: Commands) {
+    // Sun; a point light can't cover terrain that now spans hundreds of blocks
     commands.spawn((
-        PointLight {
-            intensity: 1500.0,
+        DirectionalLight {
+            illuminance: 10_000.0,
             shadows_enabled: true,
             ..Default::default()
         },
-        Transform::from_xyz(4.0, 8.0, 4.0)
+        Transform::from_xyz(0.0, 0.0, 0.0).looking_to(SUN_DIRECTION, Vec3::Y)
     ));
 }
\ No newline at end of file
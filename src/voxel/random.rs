@@ -0,0 +1,44 @@
+use bevy::prelude::*;
+
+const DEFAULT_SEED: u64 = 0x9E37_79B9_7F4A_7C15;
+
+// Small deterministic RNG (SplitMix64) so world simulation is reproducible from a seed
+#[derive(Resource, Clone)]
+pub struct WorldRng {
+    state: u64,
+}
+
+impl Default for WorldRng {
+    fn default() -> Self {
+        Self::new(DEFAULT_SEED)
+    }
+}
+
+impl WorldRng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform float in `[0, 1)`
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Uniform index in `[0, max)`
+    pub fn range(&mut self, max: usize) -> usize {
+        (self.next_u64() % max as u64) as usize
+    }
+
+    /// Uniform integer in `[min, max]`
+    pub fn range_i32(&mut self, min: i32, max: i32) -> i32 {
+        min + (self.next_u64() % (max - min + 1) as u64) as i32
+    }
+}
@@ -0,0 +1,65 @@
+use bevy::prelude::*;
+
+use crate::voxel::manager::ChunkManager;
+
+pub struct RayHit {
+    pub pos: IVec3,
+    // Face of the hit block the ray entered through, zero if the ray started inside it
+    pub normal: IVec3,
+    // Along the ray to where it entered the hit block
+    pub distance: f32,
+}
+
+/// Steps through the block grid cell by cell (Amanatides & Woo) until it meets a solid block.
+/// Unloaded chunks are passed through as if they were air.
+pub fn raycast(cm: &ChunkManager, origin: Vec3, direction: Vec3, max_distance: f32) -> Option<RayHit> {
+    let dir = direction.normalize_or_zero();
+    if dir == Vec3::ZERO {
+        return None;
+    }
+
+    let mut cell = origin.floor().as_ivec3();
+    let mut step = IVec3::ZERO;
+    // Ray distance to the next cell boundary on each axis, and between boundaries
+    let mut t_max = Vec3::INFINITY;
+    let mut t_delta = Vec3::INFINITY;
+
+    for axis in 0..3 {
+        if dir[axis] > 0.0 {
+            step[axis] = 1;
+            t_max[axis] = (cell[axis] as f32 + 1.0 - origin[axis]) / dir[axis];
+        } else if dir[axis] < 0.0 {
+            step[axis] = -1;
+            t_max[axis] = (cell[axis] as f32 - origin[axis]) / dir[axis];
+        }
+        if dir[axis] != 0.0 {
+            t_delta[axis] = 1.0 / dir[axis].abs();
+        }
+    }
+
+    let mut normal = IVec3::ZERO;
+    let mut distance = 0.0;
+
+    loop {
+        if cm.get_block_world(cell).is_some_and(|b| b.is_solid()) {
+            return Some(RayHit { pos: cell, normal, distance });
+        }
+
+        let axis = if t_max.x < t_max.y && t_max.x < t_max.z {
+            0
+        } else if t_max.y < t_max.z {
+            1
+        } else {
+            2
+        };
+        distance = t_max[axis];
+        if distance > max_distance {
+            return None;
+        }
+
+        cell[axis] += step[axis];
+        t_max[axis] += t_delta[axis];
+        normal = IVec3::ZERO;
+        normal[axis] = -step[axis];
+    }
+}
@@ -1,35 +1,141 @@
-use bevy::prelude::*;
+use std::{collections::HashMap, sync::Arc};
+
+use bevy::{pbr::NotShadowCaster, prelude::*, render::primitives::Aabb};
+use crate::player::camera::PlayerCamera;
 use crate::voxel::{
+    chunk::Chunk,
+    lod::{chunk_centre, lod_scale_for_distance, ChunkLod, MAX_LOD_SCALE},
     manager::ChunkManager,
-    mesher::generate_mesh_for_chunk,
+    material::{ChunkMaterial, AMBIENT_LIGHT, SUN_DIRECTION},
+    mesher::{generate_mesh_for_chunk, SKIRT_CELLS},
     types::CHUNK_SIZE
 };
 
-pub fn spawn_chunk_meshes(
+// Edited chunks rebuilt per frame; the rest stay dirty until the next one
+const REMESH_PER_FRAME: usize = 16;
+
+// Single material shared by every chunk, colored per block through its palette
+#[derive(Resource)]
+pub struct ChunkMaterialHandle(pub Handle<ChunkMaterial>);
+
+pub fn setup_chunk_material(
     mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
-    mut cm: ResMut<ChunkManager>,
+    mut materials: ResMut<Assets<ChunkMaterial>>,
 ) {
-    if cm.chunks.is_empty() {
-        cm.generate_test_chunk();
+    let material = materials.add(ChunkMaterial::new(SUN_DIRECTION, AMBIENT_LIGHT));
+    commands.insert_resource(ChunkMaterialHandle(material));
+}
+
+/// Shares one mesh between chunks with identical contents at the same LOD, e.g. solid stone.
+/// Holds a strong handle per mesh; `prune_chunk_mesh_cache` lets go once no chunk entity uses it.
+#[derive(Resource, Default)]
+pub struct ChunkMeshCache {
+    meshes: HashMap<(usize, Box<[u8]>), Handle<Mesh>>,
+}
+
+impl ChunkMeshCache {
+    /// Mesh for `chunk` at `lod_scale`, `None` if it would have no faces
+    pub fn mesh_for_chunk(
+        &mut self,
+        meshes: &mut Assets<Mesh>,
+        chunk: &Chunk,
+        lod_scale: usize,
+    ) -> Option<Handle<Mesh>> {
+        let key = (lod_scale, chunk.blocks.iter().map(|b| b.id).collect::<Box<[u8]>>());
+        if let Some(handle) = self.meshes.get(&key) {
+            return Some(handle.clone());
+        }
+
+        let chunkmesh = generate_mesh_for_chunk(chunk, lod_scale);
+        // All-air chunks (sky, fully carved caves) don't need a mesh
+        if chunkmesh.mesh.count_vertices() == 0 {
+            return None;
+        }
+
+        let handle = meshes.add(chunkmesh.mesh);
+        self.meshes.insert(key, handle.clone());
+        Some(handle)
     }
+}
 
-    // Create a single white material that will be tinted by vertex colors
-    let white_material = materials.add(StandardMaterial {
-        base_color: Color::WHITE,
-        ..Default::default()
+/// Drops cached meshes only the cache itself still holds, so their assets get freed
+pub fn prune_chunk_mesh_cache(mut cache: ResMut<ChunkMeshCache>) {
+    cache.meshes.retain(|_, handle| match handle {
+        Handle::Strong(strong) => Arc::strong_count(strong) > 1,
+        Handle::Weak(_) => false,
     });
+}
+
+/// Chunk-local bounds covering every block of the chunk plus the deepest LOD skirt.
+/// Set explicitly because packed meshes have no position attribute for Bevy to derive one from.
+fn chunk_aabb() -> Aabb {
+    let skirt = ((SKIRT_CELLS - 1) as usize * MAX_LOD_SCALE) as f32;
+    Aabb::from_min_max(Vec3::NEG_Y * skirt, Vec3::splat(CHUNK_SIZE as f32))
+}
+
+pub fn spawn_chunk_mesh(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    cache: &mut ChunkMeshCache,
+    material: &ChunkMaterialHandle,
+    chunk: &Chunk,
+    lod_scale: usize,
+) -> Option<Entity> {
+    // All-air chunks (sky, fully carved caves) don't need an entity
+    let mesh_handle = cache.mesh_for_chunk(meshes, chunk, lod_scale)?;
+
+    let entity = commands.spawn((
+        Mesh3d(mesh_handle),
+        MeshMaterial3d(material.0.clone()),
+        Transform::from_translation(chunk.position.as_vec3() * CHUNK_SIZE as f32),
+        Visibility::default(),
+        chunk_aabb(),
+        // The chunk shader has no shadow pass
+        NotShadowCaster,
+        ChunkLod { position: chunk.position, scale: lod_scale },
+    )).id();
+    Some(entity)
+}
+
+/// Rebuilds the meshes of chunks whose blocks changed, swapping the handle on the existing
+/// entity. Chunks that had no faces before get their entity spawned here.
+pub fn remesh_dirty_chunks(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut cache: ResMut<ChunkMeshCache>,
+    mut cm: ResMut<ChunkManager>,
+    material: Res<ChunkMaterialHandle>,
+    camera: Query<&Transform, With<PlayerCamera>>,
+    mut chunks: Query<(&ChunkLod, &mut Mesh3d)>,
+) {
+    let dirty: Vec<IVec3> = cm.chunks.values()
+        .filter(|c| c.is_dirty())
+        .map(|c| c.position)
+        .take(REMESH_PER_FRAME)
+        .collect();
+
+    for position in dirty {
+        let Some(chunk) = cm.get_chunk(position) else { continue };
+        let existing = cm.chunk_entity(position).and_then(|e| chunks.get_mut(e).ok());
 
-    for chunk in cm.chunks.iter() {
-        let chunkmesh = generate_mesh_for_chunk(chunk);
-        let mesh_handle = meshes.add(chunkmesh.mesh);
+        let spawned = match existing {
+            Some((lod, mut mesh)) => {
+                mesh.0 = cache.mesh_for_chunk(&mut meshes, chunk, lod.scale).unwrap_or_default();
+                None
+            }
+            None => {
+                let lod_scale = camera.single()
+                    .map_or(1, |t| lod_scale_for_distance(t.translation.distance(chunk_centre(position))));
+                spawn_chunk_mesh(&mut commands, &mut meshes, &mut cache, &material, chunk, lod_scale)
+            }
+        };
+        debug!("🔁 Remeshed chunk {} at revision {}", position, chunk.modifications());
 
-        commands.spawn((
-            Mesh3d(mesh_handle),
-            MeshMaterial3d(white_material.clone()),
-            Transform::from_translation(chunk.position.as_vec3() * CHUNK_SIZE as f32),
-            Visibility::default(),
-        ));
+        if let Some(entity) = spawned {
+            cm.set_chunk_entity(position, entity);
+        }
+        if let Some(chunk) = cm.get_chunk_mut(position) {
+            chunk.mark_clean();
+        }
     }
 }
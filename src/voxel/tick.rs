@@ -0,0 +1,272 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+use bevy::prelude::*;
+
+use crate::voxel::{
+    behavior::BlockBehaviors,
+    change::{BlockChangeCause, BlockChanged},
+    manager::ChunkManager,
+    random::WorldRng,
+    falling::BlockDetached,
+    types::{Block, AIR, CHUNK_SIZE}
+};
+
+pub const TICKS_PER_SECOND: f64 = 20.0;
+// Blocks picked per chunk on every random tick pass
+pub const RANDOM_TICK_SPEED: usize = 3;
+// Safety cap so a cascade of updates can't stall a single tick
+const MAX_SCHEDULED_PER_TICK: usize = 4096;
+const NEIGHBOUR_UPDATE_DELAY: u64 = 1;
+
+const NEIGHBOURS: [IVec3; 6] = [
+    IVec3::X, IVec3::NEG_X,
+    IVec3::Y, IVec3::NEG_Y,
+    IVec3::Z, IVec3::NEG_Z,
+];
+
+/// Number of fixed world ticks elapsed since startup
+#[derive(Resource, Default)]
+pub struct WorldTick(pub u64);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct ScheduledUpdate {
+    due: u64,
+    priority: i32,
+    // Insertion order keeps equal-priority updates FIFO
+    seq: u64,
+    pos: IVec3Key,
+}
+
+// IVec3 is not Ord, so the queue stores positions as plain tuples
+type IVec3Key = (i32, i32, i32);
+
+/// Priority queue of pending block updates, ordered by due tick then priority
+#[derive(Resource, Default)]
+pub struct ScheduledUpdates {
+    queue: BinaryHeap<Reverse<ScheduledUpdate>>,
+    // Due tick of the live entry per position; heap entries due later are stale
+    pending: HashMap<IVec3, u64>,
+    next_seq: u64,
+}
+
+impl ScheduledUpdates {
+    /// Schedules an update `delay` ticks after `now`; lower priority values run first.
+    /// A position that already has a pending update keeps whichever is due first.
+    pub fn schedule(&mut self, pos: IVec3, now: u64, delay: u64, priority: i32) {
+        let due = now + delay;
+        if self.pending.get(&pos).is_some_and(|&queued| queued <= due) {
+            return;
+        }
+        self.pending.insert(pos, due);
+
+        self.queue.push(Reverse(ScheduledUpdate {
+            due,
+            priority,
+            seq: self.next_seq,
+            pos: (pos.x, pos.y, pos.z),
+        }));
+        self.next_seq += 1;
+    }
+
+    /// Schedules an update for `pos` and its six neighbours, used whenever a block changes
+    pub fn notify_neighbours(&mut self, pos: IVec3, now: u64) {
+        self.schedule(pos, now, NEIGHBOUR_UPDATE_DELAY, 0);
+        for offset in NEIGHBOURS {
+            self.schedule(pos + offset, now, NEIGHBOUR_UPDATE_DELAY, 0);
+        }
+    }
+
+    fn pop_due(&mut self, now: u64) -> Option<IVec3> {
+        loop {
+            let Reverse(next) = self.queue.peek()?;
+            if next.due > now {
+                return None;
+            }
+
+            let Reverse(update) = self.queue.pop()?;
+            let pos = IVec3::new(update.pos.0, update.pos.1, update.pos.2);
+            // Entries superseded by an earlier reschedule have already run
+            if self.pending.get(&pos) == Some(&update.due) {
+                self.pending.remove(&pos);
+                return Some(pos);
+            }
+        }
+    }
+}
+
+/// World access handed to block behaviours during a tick
+pub struct TickContext<'a> {
+    pub world: &'a mut ChunkManager,
+    pub scheduled: &'a mut ScheduledUpdates,
+    pub rng: &'a mut WorldRng,
+    pub tick: u64,
+    // Blocks detached during this tick, turned into falling entities afterwards
+    pub detached: Vec<(IVec3, Block)>,
+}
+
+impl TickContext<'_> {
+    pub fn get_block(&self, pos: IVec3) -> Option<Block> {
+        self.world.get_block_world(pos)
+    }
+
+    /// Sets a block and notifies it and its six neighbours on the next tick
+    pub fn set_block(&mut self, pos: IVec3, block: Block) -> Option<Block> {
+        let old = self.world.set_block_world(pos, block, BlockChangeCause::Tick)?;
+        self.notify_neighbours(pos);
+        Some(old)
+    }
+
+    pub fn schedule(&mut self, pos: IVec3, delay: u64) {
+        self.scheduled.schedule(pos, self.tick, delay, 0);
+    }
+
+    pub fn notify_neighbours(&mut self, pos: IVec3) {
+        self.scheduled.notify_neighbours(pos, self.tick);
+    }
+
+    /// Removes a block from the world so it can continue as a falling entity
+    pub fn detach_block(&mut self, pos: IVec3, block: Block) {
+        if self.set_block(pos, AIR).is_some() {
+            self.detached.push((pos, block));
+        }
+    }
+}
+
+/// Wakes the neighbours of blocks changed outside the tick, e.g. by the player or undo,
+/// so sand loses its support and grass notices it was covered
+pub fn notify_external_changes(
+    tick: Res<WorldTick>,
+    mut scheduled: ResMut<ScheduledUpdates>,
+    mut events: EventReader<BlockChanged>,
+) {
+    for change in events.read() {
+        // Ticks and landing blocks notify their neighbours themselves
+        if !matches!(change.cause, BlockChangeCause::Tick | BlockChangeCause::Physics) {
+            scheduled.notify_neighbours(change.pos, tick.0);
+        }
+    }
+}
+
+pub fn advance_world_tick(mut tick: ResMut<WorldTick>) {
+    tick.0 += 1;
+}
+
+pub fn run_random_ticks(
+    tick: Res<WorldTick>,
+    behaviors: Res<BlockBehaviors>,
+    mut cm: ResMut<ChunkManager>,
+    mut scheduled: ResMut<ScheduledUpdates>,
+    mut rng: ResMut<WorldRng>,
+    mut detached_events: EventWriter<BlockDetached>,
+) {
+    let origins: Vec<IVec3> = cm.chunks.values().map(|c| c.world_origin()).collect();
+
+    let mut ctx = TickContext {
+        world: &mut cm,
+        scheduled: &mut scheduled,
+        rng: &mut rng,
+        tick: tick.0,
+        detached: Vec::new(),
+    };
+
+    for origin in origins {
+        for _ in 0..RANDOM_TICK_SPEED {
+            let local = IVec3::new(
+                ctx.rng.range(CHUNK_SIZE) as i32,
+                ctx.rng.range(CHUNK_SIZE) as i32,
+                ctx.rng.range(CHUNK_SIZE) as i32,
+            );
+            let pos = origin + local;
+
+            let Some(block) = ctx.get_block(pos) else { continue };
+            if let Some(behavior) = behaviors.get(block) {
+                behavior.random_tick(&mut ctx, pos, block);
+            }
+        }
+    }
+
+    for (pos, block) in ctx.detached {
+        detached_events.write(BlockDetached { pos, block });
+    }
+}
+
+pub fn run_scheduled_ticks(
+    tick: Res<WorldTick>,
+    behaviors: Res<BlockBehaviors>,
+    mut cm: ResMut<ChunkManager>,
+    mut scheduled: ResMut<ScheduledUpdates>,
+    mut rng: ResMut<WorldRng>,
+    mut detached_events: EventWriter<BlockDetached>,
+) {
+    let mut ctx = TickContext {
+        world: &mut cm,
+        scheduled: &mut scheduled,
+        rng: &mut rng,
+        tick: tick.0,
+        detached: Vec::new(),
+    };
+
+    for _ in 0..MAX_SCHEDULED_PER_TICK {
+        let Some(pos) = ctx.scheduled.pop_due(ctx.tick) else { break };
+
+        let Some(block) = ctx.get_block(pos) else { continue };
+        if let Some(behavior) = behaviors.get(block) {
+            behavior.scheduled_tick(&mut ctx, pos, block);
+        }
+    }
+
+    for (pos, block) in ctx.detached {
+        detached_events.write(BlockDetached { pos, block });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drain(scheduled: &mut ScheduledUpdates, now: u64) -> Vec<IVec3> {
+        std::iter::from_fn(|| scheduled.pop_due(now)).collect()
+    }
+
+    #[test]
+    fn updates_run_by_due_tick_then_priority_then_order() {
+        let mut scheduled = ScheduledUpdates::default();
+        scheduled.schedule(IVec3::X, 0, 5, 0);
+        scheduled.schedule(IVec3::Y, 0, 2, 1);
+        scheduled.schedule(IVec3::Z, 0, 2, 0);
+        scheduled.schedule(IVec3::NEG_X, 0, 2, 1);
+
+        assert!(scheduled.pop_due(1).is_none());
+        assert_eq!(drain(&mut scheduled, 2), [IVec3::Z, IVec3::Y, IVec3::NEG_X]);
+        assert!(scheduled.pop_due(4).is_none());
+        assert_eq!(drain(&mut scheduled, 5), [IVec3::X]);
+    }
+
+    #[test]
+    fn rescheduling_keeps_the_earlier_due_tick() {
+        let mut scheduled = ScheduledUpdates::default();
+        scheduled.schedule(IVec3::ZERO, 0, 2, 0);
+        scheduled.schedule(IVec3::ZERO, 0, 8, 0);
+        assert_eq!(drain(&mut scheduled, 2), [IVec3::ZERO]);
+        assert!(drain(&mut scheduled, 8).is_empty());
+
+        scheduled.schedule(IVec3::X, 0, 8, 0);
+        scheduled.schedule(IVec3::X, 0, 3, 0);
+        assert_eq!(drain(&mut scheduled, 3), [IVec3::X]);
+        assert!(drain(&mut scheduled, 8).is_empty());
+    }
+
+    #[test]
+    fn superseded_entries_are_skipped() {
+        let mut scheduled = ScheduledUpdates::default();
+        // The first entry goes stale when the position is moved earlier
+        scheduled.schedule(IVec3::ZERO, 0, 6, 0);
+        scheduled.schedule(IVec3::ZERO, 0, 1, 0);
+        scheduled.schedule(IVec3::Y, 0, 4, 0);
+
+        assert_eq!(drain(&mut scheduled, 10), [IVec3::ZERO, IVec3::Y]);
+        assert!(scheduled.queue.is_empty());
+        assert!(scheduled.pending.is_empty());
+    }
+}
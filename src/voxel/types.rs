@@ -1,42 +1,93 @@
 use bevy::prelude::*;
 
+/// Which tool mines a block faster
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockCategory {
+    Generic,
+    Stone,
+    Wood,
+    Soil,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Block {
     pub id: u8,
     pub name: &'static str,
     pub color: Color,
+    // Detaches and falls when the block below is not solid
+    pub gravity: bool,
+    // Seconds to mine by hand in survival; negative can't be mined at all
+    pub hardness: f32,
+    pub category: BlockCategory,
 }
 
 impl Block {
     pub const fn new(id: u8, name: &'static str, color: Color) -> Self {
-        Self { id, name, color }
+        Self { id, name, color, gravity: false, hardness: 1.0, category: BlockCategory::Generic }
+    }
+
+    pub const fn with_hardness(mut self, hardness: f32) -> Self {
+        self.hardness = hardness;
+        self
+    }
+
+    pub const fn with_gravity(mut self) -> Self {
+        self.gravity = true;
+        self
+    }
+
+    pub const fn in_category(mut self, category: BlockCategory) -> Self {
+        self.category = category;
+        self
+    }
+
+    pub const fn is_fluid(&self) -> bool {
+        self.id == WATER.id || self.id == LAVA.id
+    }
+
+    // Solid blocks support gravity blocks and collide with entities
+    pub const fn is_solid(&self) -> bool {
+        self.id != AIR.id && !self.is_fluid()
     }
 }
 
 // Predefined block types
-pub const AIR: Block = Block::new(0, "Air", Color::srgba(0.0, 0.0, 0.0, 0.0));
-pub const GRASS: Block = Block::new(1, "Grass", Color::srgba(0.2, 0.8, 0.2, 1.0));
-pub const DIRT: Block = Block::new(2, "Dirt", Color::srgba(0.6, 0.4, 0.2, 1.0));
-pub const STONE: Block = Block::new(3, "Stone", Color::srgba(0.5, 0.5, 0.5, 1.0));
-pub const SAND: Block = Block::new(4, "Sand", Color::srgba(0.76, 0.7, 0.5, 1.0));
-pub const WATER: Block = Block::new(5, "Water", Color::srgba(0.0, 0.5, 0.8, 0.7));
-pub const WOOD: Block = Block::new(6, "Wood", Color::srgba(0.6, 0.4, 0.2, 1.0));
-pub const LEAVES: Block = Block::new(7, "Leaves", Color::srgba(0.1, 0.6, 0.1, 1.0));
+pub const AIR: Block = Block::new(0, "Air", Color::srgba(0.0, 0.0, 0.0, 0.0)).with_hardness(-1.0);
+pub const GRASS: Block = Block::new(1, "Grass", Color::srgba(0.2, 0.8, 0.2, 1.0))
+    .with_hardness(0.9).in_category(BlockCategory::Soil);
+pub const DIRT: Block = Block::new(2, "Dirt", Color::srgba(0.6, 0.4, 0.2, 1.0))
+    .with_hardness(0.75).in_category(BlockCategory::Soil);
+pub const STONE: Block = Block::new(3, "Stone", Color::srgba(0.5, 0.5, 0.5, 1.0))
+    .with_hardness(7.5).in_category(BlockCategory::Stone);
+pub const SAND: Block = Block::new(4, "Sand", Color::srgba(0.76, 0.7, 0.5, 1.0))
+    .with_gravity().with_hardness(0.75).in_category(BlockCategory::Soil);
+pub const WATER: Block = Block::new(5, "Water", Color::srgba(0.0, 0.5, 0.8, 0.7)).with_hardness(-1.0);
+pub const WOOD: Block = Block::new(6, "Wood", Color::srgba(0.6, 0.4, 0.2, 1.0))
+    .with_hardness(3.0).in_category(BlockCategory::Wood);
+pub const LEAVES: Block = Block::new(7, "Leaves", Color::srgba(0.1, 0.6, 0.1, 1.0)).with_hardness(0.35);
+pub const LAVA: Block = Block::new(8, "Lava", Color::srgba(0.9, 0.35, 0.05, 1.0)).with_hardness(-1.0);
+pub const COAL_ORE: Block = Block::new(9, "Coal Ore", Color::srgba(0.2, 0.2, 0.2, 1.0))
+    .with_hardness(15.0).in_category(BlockCategory::Stone);
+pub const IRON_ORE: Block = Block::new(10, "Iron Ore", Color::srgba(0.72, 0.56, 0.45, 1.0))
+    .with_hardness(15.0).in_category(BlockCategory::Stone);
+pub const GOLD_ORE: Block = Block::new(11, "Gold Ore", Color::srgba(0.95, 0.8, 0.2, 1.0))
+    .with_hardness(15.0).in_category(BlockCategory::Stone);
+pub const DIAMOND_ORE: Block = Block::new(12, "Diamond Ore", Color::srgba(0.4, 0.9, 0.9, 1.0))
+    .with_hardness(15.0).in_category(BlockCategory::Stone);
+pub const PLANKS: Block = Block::new(13, "Planks", Color::srgba(0.76, 0.6, 0.38, 1.0))
+    .with_hardness(3.0).in_category(BlockCategory::Wood);
+pub const CRAFTING_TABLE: Block = Block::new(14, "Crafting Table", Color::srgba(0.55, 0.36, 0.2, 1.0))
+    .with_hardness(3.75).in_category(BlockCategory::Wood);
 
 // Block registry for easy lookup
-// pub fn get_block_by_id(id: u8) -> Option<Block> {
-//     match id {
-//         0 => Some(AIR),
-//         1 => Some(GRASS),
-//         2 => Some(DIRT),
-//         3 => Some(STONE),
-//         4 => Some(SAND),
-//         5 => Some(WATER),
-//         6 => Some(WOOD),
-//         7 => Some(LEAVES),
-//         _ => None,
-//     }
-// }
+pub const BLOCKS: [Block; 15] = [
+    AIR, GRASS, DIRT, STONE, SAND, WATER, WOOD, LEAVES,
+    LAVA, COAL_ORE, IRON_ORE, GOLD_ORE, DIAMOND_ORE, PLANKS, CRAFTING_TABLE,
+];
+
+pub fn get_block_by_name(name: &str) -> Option<Block> {
+    BLOCKS.iter().copied().find(|b| b.name.eq_ignore_ascii_case(name))
+}
 
 pub const CHUNK_SIZE: usize = 16;
 pub const CHUNK_VOLUME: usize = CHUNK_SIZE * CHUNK_SIZE * CHUNK_SIZE;
\ No newline at end of file
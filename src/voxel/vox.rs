@@ -0,0 +1,251 @@
+use bevy::{color::ColorToPacked, prelude::*};
+
+use crate::voxel::{
+    generation::schematic::Schematic,
+    types::{Block, AIR, BLOCKS}
+};
+
+// MagicaVoxel models are capped at 256 voxels per axis
+const MAX_VOX_SIZE: u32 = 256;
+const VOX_VERSION: u32 = 150;
+// Channel levels of MagicaVoxel's default palette: a colour cube, then red, green, blue and grey ramps
+const DEFAULT_CUBE_STEPS: [u8; 6] = [0xFF, 0xCC, 0x99, 0x66, 0x33, 0x00];
+const DEFAULT_RAMP_STEPS: [u8; 10] = [0xEE, 0xDD, 0xBB, 0xAA, 0x88, 0x77, 0x55, 0x44, 0x22, 0x11];
+
+/// Reads the first model of a MagicaVoxel `.vox` file, mapping each palette colour to the
+/// block `write_vox` keeps at that index, or else the block with the nearest `Block::color`.
+/// MagicaVoxel is Z-up, so its axes are turned to Y-up.
+/// Files without an RGBA chunk use MagicaVoxel's default palette, and models holding more
+/// than `max_blocks` cells are refused before any of them is allocated.
+pub fn read_vox(bytes: &[u8], max_blocks: usize) -> Result<Schematic, String> {
+    let mut reader = Reader { bytes, pos: 0 };
+    if reader.take(4)? != b"VOX " {
+        return Err("not a .vox file".into());
+    }
+    reader.u32()?;
+
+    let (id, content, children) = reader.chunk_header()?;
+    if id != b"MAIN" {
+        return Err("missing MAIN chunk".into());
+    }
+    reader.take(content)?;
+    let mut children = Reader { bytes: reader.take(children)?, pos: 0 };
+
+    let mut size: Option<UVec3> = None;
+    let mut voxels: Option<Vec<[u8; 4]>> = None;
+    let mut palette: Option<Vec<[u8; 4]>> = None;
+
+    while children.pos < children.bytes.len() {
+        let (id, content, nested) = children.chunk_header()?;
+        let mut data = Reader { bytes: children.take(content)?, pos: 0 };
+        children.take(nested)?;
+
+        match id {
+            // Only the first model is read, later ones belong to multi-model scenes
+            b"SIZE" if size.is_none() => size = Some(UVec3::new(data.u32()?, data.u32()?, data.u32()?)),
+            b"XYZI" if voxels.is_none() => {
+                let count = data.u32()? as usize;
+                voxels = Some((0..count).map(|_| data.array()).collect::<Result<_, _>>()?);
+            }
+            b"RGBA" => palette = Some((0..256).map(|_| data.array()).collect::<Result<_, _>>()?),
+            _ => {}
+        }
+    }
+
+    let size = size.ok_or("missing SIZE chunk")?;
+    let voxels = voxels.ok_or("missing XYZI chunk")?;
+    let palette = palette.unwrap_or_else(default_palette);
+    if size.cmpgt(UVec3::splat(MAX_VOX_SIZE)).any() || size.cmpeq(UVec3::ZERO).any() {
+        return Err(format!("bad model size {}", size));
+    }
+    if size.x as usize * size.y as usize * size.z as usize > max_blocks {
+        return Err(format!("model is larger than {} blocks", max_blocks));
+    }
+
+    // Colour index i lives at palette[i - 1]
+    let blocks: Vec<Block> = palette.iter()
+        .enumerate()
+        .map(|(i, c)| indexed_block(i + 1, *c).unwrap_or_else(|| nearest_block(*c)))
+        .collect();
+
+    let mut schematic = Schematic::new(UVec3::new(size.x, size.z, size.y));
+    for [x, y, z, index] in voxels {
+        let (x, y, z) = (x as u32, y as u32, z as u32);
+        if index == 0 || x >= size.x || y >= size.y || z >= size.z {
+            continue;
+        }
+        schematic.set(x, z, size.y - 1 - y, Some(blocks[index as usize - 1]));
+    }
+
+    Ok(schematic)
+}
+
+/// Writes a schematic as a single-model `.vox`; air and structure void are left empty.
+/// Every block sits at the colour index equal to its id, so blocks sharing a colour read back apart
+pub fn write_vox(schematic: &Schematic) -> Result<Vec<u8>, String> {
+    let size = schematic.size;
+    if size.cmpgt(UVec3::splat(MAX_VOX_SIZE)).any() {
+        return Err(format!("region is larger than {} blocks on a side", MAX_VOX_SIZE));
+    }
+
+    let mut voxels: Vec<u8> = Vec::new();
+    let mut count = 0u32;
+
+    for z in 0..size.z {
+        for y in 0..size.y {
+            for x in 0..size.x {
+                let Some(block) = schematic.get(x, y, z).filter(|b| b.id != AIR.id) else { continue };
+                // Back to Z-up, see `read_vox`
+                voxels.extend([x as u8, (size.z - 1 - z) as u8, y as u8, block.id]);
+                count += 1;
+            }
+        }
+    }
+
+    let mut rgba = Vec::with_capacity(256 * 4);
+    for i in 1..=256 {
+        let color = BLOCKS.iter().find(|b| b.id as usize == i).map_or([0; 4], |b| b.color.to_srgba().to_u8_array());
+        rgba.extend(color);
+    }
+
+    let mut children = Vec::new();
+    write_chunk(&mut children, b"SIZE", &[size.x, size.z, size.y].map(u32::to_le_bytes).concat());
+    write_chunk(&mut children, b"XYZI", &[count.to_le_bytes().as_slice(), &voxels].concat());
+    write_chunk(&mut children, b"RGBA", &rgba);
+
+    let mut bytes = Vec::with_capacity(children.len() + 20);
+    bytes.extend_from_slice(b"VOX ");
+    bytes.extend(VOX_VERSION.to_le_bytes());
+    bytes.extend_from_slice(b"MAIN");
+    bytes.extend(0u32.to_le_bytes());
+    bytes.extend((children.len() as u32).to_le_bytes());
+    bytes.extend(children);
+    Ok(bytes)
+}
+
+// Laid out like an RGBA chunk, entry i - 1 is colour index i
+fn default_palette() -> Vec<[u8; 4]> {
+    let steps = &DEFAULT_CUBE_STEPS;
+    let cube = steps.iter().flat_map(|&r| {
+        steps.iter().flat_map(move |&g| steps.iter().map(move |&b| [r, g, b, 0xFF]))
+    });
+    let ramps = [[1, 0, 0], [0, 1, 0], [0, 0, 1], [1, 1, 1]].into_iter().flat_map(|[r, g, b]| {
+        DEFAULT_RAMP_STEPS.iter().map(move |&v| [v * r, v * g, v * b, 0xFF])
+    });
+
+    // Black is left out of the cube, the last entry is unused
+    cube.filter(|c| c[..3] != [0, 0, 0]).chain(ramps).chain([[0; 4]]).collect()
+}
+
+fn write_chunk(out: &mut Vec<u8>, id: &[u8; 4], content: &[u8]) {
+    out.extend_from_slice(id);
+    out.extend((content.len() as u32).to_le_bytes());
+    out.extend(0u32.to_le_bytes());
+    out.extend_from_slice(content);
+}
+
+// The block `write_vox` puts at colour `index`, if the file still has its colour there
+fn indexed_block(index: usize, rgba: [u8; 4]) -> Option<Block> {
+    BLOCKS.iter()
+        .find(|b| b.id != AIR.id && b.id as usize == index && b.color.to_srgba().to_u8_array() == rgba)
+        .copied()
+}
+
+fn nearest_block(rgba: [u8; 4]) -> Block {
+    let target = Vec3::new(rgba[0] as f32, rgba[1] as f32, rgba[2] as f32) / 255.0;
+    BLOCKS.iter()
+        .filter(|b| b.id != AIR.id)
+        .min_by(|a, b| {
+            let da = color_distance(a.color, target);
+            let db = color_distance(b.color, target);
+            da.total_cmp(&db)
+        })
+        .copied()
+        .unwrap_or(AIR)
+}
+
+fn color_distance(color: Color, target: Vec3) -> f32 {
+    let c = color.to_srgba();
+    Vec3::new(c.red, c.green, c.blue).distance_squared(target)
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let end = self.pos.checked_add(len).filter(|&end| end <= self.bytes.len()).ok_or("unexpected end of file")?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn array(&mut self) -> Result<[u8; 4], String> {
+        let bytes = self.take(4)?;
+        Ok([bytes[0], bytes[1], bytes[2], bytes[3]])
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        self.array().map(u32::from_le_bytes)
+    }
+
+    // Chunk id, content length and children length
+    fn chunk_header(&mut self) -> Result<(&'a [u8], usize, usize), String> {
+        let id = self.take(4)?;
+        Ok((id, self.u32()? as usize, self.u32()? as usize))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::voxel::types::{DIRT, GRASS, LEAVES, SAND, STONE, WOOD};
+
+    fn model() -> Schematic {
+        let mut schematic = Schematic::new(UVec3::new(3, 2, 4));
+        schematic.set(0, 0, 0, Some(STONE));
+        schematic.set(2, 0, 3, Some(SAND));
+        schematic.set(1, 1, 2, Some(LEAVES));
+        schematic.set(2, 1, 0, Some(GRASS));
+        schematic.set(0, 1, 3, Some(STONE));
+        // Same colour, told apart by their palette index
+        schematic.set(1, 0, 1, Some(DIRT));
+        schematic.set(1, 1, 1, Some(WOOD));
+        schematic
+    }
+
+    #[test]
+    fn round_trip_keeps_size_and_blocks() {
+        let original = model();
+        let read = read_vox(&write_vox(&original).unwrap(), usize::MAX).unwrap();
+
+        assert_eq!(read.size, original.size);
+        for z in 0..original.size.z {
+            for y in 0..original.size.y {
+                for x in 0..original.size.x {
+                    let id = |s: &Schematic| s.get(x, y, z).map(|b| b.id);
+                    assert_eq!(id(&read), id(&original), "block at {x} {y} {z}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn missing_palette_uses_the_default() {
+        assert_eq!(default_palette().len(), 256);
+
+        // Drop the trailing RGBA chunk and shrink MAIN to match
+        let mut bytes = write_vox(&model()).unwrap();
+        let rgba = 12 + 256 * 4;
+        bytes.truncate(bytes.len() - rgba);
+        let children = u32::from_le_bytes(bytes[16..20].try_into().unwrap()) - rgba as u32;
+        bytes[16..20].copy_from_slice(&children.to_le_bytes());
+
+        let read = read_vox(&bytes, usize::MAX).unwrap();
+        assert_eq!(read.size, UVec3::new(3, 2, 4));
+        assert!(read.get(0, 0, 0).is_some());
+        assert!(read.get(1, 0, 0).is_none());
+    }
+}
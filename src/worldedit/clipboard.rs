@@ -0,0 +1,91 @@
+use bevy::prelude::*;
+
+use crate::voxel::{
+    change::BlockChangeCause,
+    generation::schematic::Schematic,
+    manager::ChunkManager,
+    types::{Block, AIR}
+};
+
+/// A copied region, positioned relative to where the player stood when copying
+pub struct Clipboard {
+    pub schematic: Schematic,
+    // From the player's block to the schematic's minimum corner
+    pub offset: IVec3,
+}
+
+impl Clipboard {
+    /// Copies the box between `min` and `max`; blocks in unloaded chunks become structure void
+    pub fn copy(cm: &ChunkManager, min: IVec3, max: IVec3, origin: IVec3) -> Self {
+        let mut schematic = Schematic::new((max - min + IVec3::ONE).as_uvec3());
+        for (pos, block) in cm.blocks_in_aabb(min, max) {
+            let local = (pos - min).as_uvec3();
+            schematic.set(local.x, local.y, local.z, block);
+        }
+
+        Self { schematic, offset: min - origin }
+    }
+
+    // Every non-void cell, relative to the paste origin
+    fn cells(&self) -> impl Iterator<Item = (IVec3, Block)> + '_ {
+        let size = self.schematic.size;
+        (0..size.z).flat_map(move |z| {
+            (0..size.y).flat_map(move |y| {
+                (0..size.x).filter_map(move |x| {
+                    let block = self.schematic.get(x, y, z)?;
+                    Some((self.offset + UVec3::new(x, y, z).as_ivec3(), block))
+                })
+            })
+        })
+    }
+
+    /// Writes the clipboard relative to `origin`, optionally leaving existing blocks where it holds air.
+    /// Returns how many blocks changed
+    pub fn paste(&self, cm: &mut ChunkManager, origin: IVec3, skip_air: bool) -> usize {
+        self.cells()
+            .filter(|(_, block)| !skip_air || block.id != AIR.id)
+            .filter(|(pos, block)| {
+                cm.set_block_world(origin + *pos, *block, BlockChangeCause::Edit)
+                    .is_some_and(|old| old.id != block.id)
+            })
+            .count()
+    }
+
+    /// Remaps every cell around the paste origin and rebuilds the bounding schematic
+    fn transformed(&self, map: impl Fn(IVec3) -> IVec3) -> Self {
+        let cells: Vec<(IVec3, Block)> = self.cells().map(|(pos, block)| (map(pos), block)).collect();
+        let Some(first) = cells.first() else {
+            return Self { schematic: self.schematic.clone(), offset: self.offset };
+        };
+
+        let (min, max) = cells.iter()
+            .fold((first.0, first.0), |(min, max), (pos, _)| (min.min(*pos), max.max(*pos)));
+
+        let mut schematic = Schematic::new((max - min + IVec3::ONE).as_uvec3());
+        for (pos, block) in cells {
+            let local = (pos - min).as_uvec3();
+            schematic.set(local.x, local.y, local.z, Some(block));
+        }
+
+        Self { schematic, offset: min }
+    }
+
+    /// Rotates clockwise (seen from above) about the vertical axis through the paste origin
+    pub fn rotated(&self, quarter_turns: i32) -> Self {
+        let turns = quarter_turns.rem_euclid(4);
+        self.transformed(|mut p| {
+            for _ in 0..turns {
+                p = IVec3::new(-p.z, p.y, p.x);
+            }
+            p
+        })
+    }
+
+    /// Mirrors across the plane through the paste origin facing `axis` (0 = x, 1 = y, 2 = z)
+    pub fn mirrored(&self, axis: usize) -> Self {
+        self.transformed(|mut p| {
+            p[axis] = -p[axis];
+            p
+        })
+    }
+}
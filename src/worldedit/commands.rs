@@ -0,0 +1,243 @@
+use bevy::prelude::*;
+
+use crate::console::command::ConsoleCommand;
+use crate::player::{body::{look_ray, Player}, camera::CameraController};
+use crate::voxel::{
+    change::BlockChangeCause,
+    export::ExportMesh,
+    history::EditHistory,
+    manager::ChunkManager,
+    raycast::raycast,
+    types::{get_block_by_name, Block, AIR},
+    vox::{read_vox, write_vox}
+};
+use crate::worldedit::{
+    clipboard::Clipboard,
+    selection::{volume, Selection},
+    shapes::{cylinder, interior, set_blocks, sphere, walls}
+};
+
+// Refuse edits bigger than this so a typo can't freeze the game
+const MAX_EDIT_BLOCKS: usize = 262_144;
+const MAX_BRUSH_RADIUS: i32 = 32;
+// Brushes are centred on the block the player looks at within this range
+const BRUSH_REACH: f32 = 64.0;
+const SCHEMATIC_DIR: &str = "assets/schematics";
+const VOX_DIR: &str = "assets/vox";
+// Rendered snapshots are for artists, not game assets
+const MESH_EXPORT_DIR: &str = "exports";
+
+#[derive(Resource, Default)]
+pub struct CurrentClipboard(pub Option<Clipboard>);
+
+type EditResult = Result<String, String>;
+
+// Block names use underscores in commands, e.g. `coal_ore`
+fn parse_block(arg: Option<&str>) -> Result<Block, String> {
+    let name = arg.ok_or("missing block name")?;
+    get_block_by_name(&name.replace('_', " ")).ok_or(format!("unknown block '{}'", name))
+}
+
+fn parse_number(arg: Option<&str>, what: &str) -> Result<i32, String> {
+    arg.ok_or(format!("missing {}", what))?
+        .parse()
+        .map_err(|_| format!("{} must be a whole number", what))
+}
+
+fn parse_radius(arg: Option<&str>) -> Result<i32, String> {
+    let radius = parse_number(arg, "radius")?;
+    if !(1..=MAX_BRUSH_RADIUS).contains(&radius) {
+        return Err(format!("radius must be between 1 and {}", MAX_BRUSH_RADIUS));
+    }
+    Ok(radius)
+}
+
+fn selected_region(selection: &Selection) -> Result<(IVec3, IVec3), String> {
+    let (min, max) = selection.bounds().ok_or("select two corners first (//wand, //pos1, //pos2)")?;
+    if volume(min, max) > MAX_EDIT_BLOCKS {
+        return Err(format!("selection is larger than {} blocks", MAX_EDIT_BLOCKS));
+    }
+    Ok((min, max))
+}
+
+/// Building tool commands, typed into the console with a double slash (`//set stone`)
+pub fn run_edit_commands(
+    mut commands: EventReader<ConsoleCommand>,
+    mut selection: ResMut<Selection>,
+    mut clipboard: ResMut<CurrentClipboard>,
+    mut history: ResMut<EditHistory>,
+    mut cm: ResMut<ChunkManager>,
+    player: Query<(&Transform, &CameraController), With<Player>>,
+) {
+    let Ok((body, controller)) = player.single() else { return };
+    let (eye, look) = look_ray(body, controller);
+    let player = body.translation.floor().as_ivec3();
+    let target = raycast(&cm, eye, look, BRUSH_REACH)
+        .map_or(player, |hit| hit.pos);
+
+    for command in commands.read() {
+        let Some(name) = command.name.strip_prefix('/') else { continue };
+
+        let result: EditResult = match name {
+            "wand" => {
+                selection.wand = !selection.wand;
+                Ok(format!("Wand {}", if selection.wand { "out" } else { "put away" }))
+            }
+            "pos1" => {
+                selection.pos1 = Some(player);
+                Ok(format!("First corner set to {}", player))
+            }
+            "pos2" => {
+                selection.pos2 = Some(player);
+                Ok(format!("Second corner set to {}", player))
+            }
+            "desel" => {
+                selection.pos1 = None;
+                selection.pos2 = None;
+                Ok("Selection cleared".into())
+            }
+            "set" => parse_block(command.arg(0)).and_then(|block| {
+                let (min, max) = selected_region(&selection)?;
+                let changed = cm.fill_region(min, max, block, BlockChangeCause::Edit);
+                Ok(format!("{} blocks set to {}", changed, block.name))
+            }),
+            "replace" => parse_block(command.arg(0)).and_then(|from| {
+                let to = parse_block(command.arg(1))?;
+                let (min, max) = selected_region(&selection)?;
+                let changed = cm.replace_region(min, max, from, to, BlockChangeCause::Edit);
+                Ok(format!("{} {} blocks replaced with {}", changed, from.name, to.name))
+            }),
+            "hollow" => selected_region(&selection).map(|(min, max)| {
+                let changed = set_blocks(&mut cm, interior(min, max), AIR);
+                format!("{} blocks hollowed out", changed)
+            }),
+            "walls" => parse_block(command.arg(0)).and_then(|block| {
+                let (min, max) = selected_region(&selection)?;
+                let changed = set_blocks(&mut cm, walls(min, max), block);
+                Ok(format!("{} wall blocks set to {}", changed, block.name))
+            }),
+            "sphere" => parse_block(command.arg(0)).and_then(|block| {
+                let radius = parse_radius(command.arg(1))?;
+                let changed = set_blocks(&mut cm, sphere(target, radius, command.has_flag("-h")), block);
+                Ok(format!("{} blocks set in a sphere at {}", changed, target))
+            }),
+            "cyl" => parse_block(command.arg(0)).and_then(|block| {
+                let radius = parse_radius(command.arg(1))?;
+                let height = match command.arg(2).filter(|a| !a.starts_with('-')) {
+                    Some(arg) => parse_number(Some(arg), "height")?,
+                    None => 1,
+                };
+                let shape = cylinder(target, radius, height.min(MAX_BRUSH_RADIUS * 2), command.has_flag("-h"));
+                let changed = set_blocks(&mut cm, shape, block);
+                Ok(format!("{} blocks set in a cylinder at {}", changed, target))
+            }),
+            "copy" | "cut" => selected_region(&selection).map(|(min, max)| {
+                clipboard.0 = Some(Clipboard::copy(&cm, min, max, player));
+                if name == "cut" {
+                    let changed = cm.fill_region(min, max, AIR, BlockChangeCause::Edit);
+                    format!("{} blocks cut", changed)
+                } else {
+                    format!("{} blocks copied", volume(min, max))
+                }
+            }),
+            "paste" => clipboard.0.as_ref().ok_or("clipboard is empty".to_string()).and_then(|clip| {
+                // Clipboards also come from files, so they are held to the selection limit here
+                let size = clip.schematic.size;
+                if size.x as usize * size.y as usize * size.z as usize > MAX_EDIT_BLOCKS {
+                    return Err(format!("clipboard is larger than {} blocks", MAX_EDIT_BLOCKS));
+                }
+                let changed = clip.paste(&mut cm, player, command.has_flag("-a"));
+                Ok(format!("{} blocks pasted", changed))
+            }),
+            "rotate" => parse_number(command.arg(0), "angle").and_then(|degrees| {
+                if degrees % 90 != 0 {
+                    return Err("angle must be a multiple of 90".into());
+                }
+                let clip = clipboard.0.as_ref().ok_or("clipboard is empty")?;
+                clipboard.0 = Some(clip.rotated(degrees / 90));
+                Ok(format!("Clipboard rotated by {} degrees", degrees))
+            }),
+            "flip" => {
+                let axis = match command.arg(0).unwrap_or("x") {
+                    "x" => Ok(0),
+                    "y" => Ok(1),
+                    "z" => Ok(2),
+                    other => Err(format!("unknown axis '{}'", other)),
+                };
+                axis.and_then(|axis| {
+                    let clip = clipboard.0.as_ref().ok_or("clipboard is empty")?;
+                    clipboard.0 = Some(clip.mirrored(axis));
+                    Ok("Clipboard flipped".to_string())
+                })
+            }
+            "export" => export_clipboard(clipboard.0.as_ref(), command.arg(0)),
+            "meshexport" => export_meshes(&cm, &selection, command.arg(0), command.arg(1)),
+            "voximport" => file_path(VOX_DIR, command.arg(0), "vox").and_then(|path| {
+                let bytes = std::fs::read(&path).map_err(|e| format!("could not read {}: {}", path, e))?;
+                let schematic = read_vox(&bytes, MAX_EDIT_BLOCKS)?;
+                let size = schematic.size;
+                clipboard.0 = Some(Clipboard { schematic, offset: IVec3::ZERO });
+                Ok(format!("Loaded {} ({}x{}x{}) into the clipboard, place it with //paste", path, size.x, size.y, size.z))
+            }),
+            "voxexport" => file_path(VOX_DIR, command.arg(0), "vox").and_then(|path| {
+                let (min, max) = selected_region(&selection)?;
+                let bytes = write_vox(&Clipboard::copy(&cm, min, max, min).schematic)?;
+                std::fs::create_dir_all(VOX_DIR).map_err(|e| format!("could not create {}: {}", VOX_DIR, e))?;
+                std::fs::write(&path, bytes).map_err(|e| format!("could not write {}: {}", path, e))?;
+                Ok(format!("Selection exported to {}", path))
+            }),
+            "undo" => history.undo(&mut cm)
+                .map(|count| format!("Undid {} block changes", count))
+                .ok_or("nothing to undo".to_string()),
+            "redo" => history.redo(&mut cm)
+                .map(|count| format!("Redid {} block changes", count))
+                .ok_or("nothing to redo".to_string()),
+            _ => Err("unknown command".into()),
+        };
+
+        match result {
+            Ok(message) => info!("✏️ {}", message),
+            Err(err) => warn!("⚠️ //{}: {}", name, err),
+        }
+    }
+}
+
+// Keeps typed file names inside their asset folder
+fn file_path(dir: &str, name: Option<&str>, extension: &str) -> Result<String, String> {
+    let name = name.ok_or("missing file name")?;
+    if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+        return Err("file names may only use letters, digits, '_' and '-'".into());
+    }
+    Ok(format!("{}/{}.{}", dir, name, extension))
+}
+
+// Writes the full resolution meshes of every chunk touching the selection
+fn export_meshes(cm: &ChunkManager, selection: &Selection, name: Option<&str>, format: Option<&str>) -> EditResult {
+    let (min, max) = selected_region(selection)?;
+    let (min_chunk, _) = ChunkManager::world_to_local(min);
+    let (max_chunk, _) = ChunkManager::world_to_local(max);
+
+    let mesh = ExportMesh::from_chunks(cm, min_chunk, max_chunk, min);
+    if mesh.is_empty() {
+        return Err("nothing to export, the selected chunks are empty".into());
+    }
+
+    let (bytes, extension) = match format.unwrap_or("glb") {
+        "glb" | "gltf" => (mesh.to_glb(), "glb"),
+        "obj" => (mesh.to_obj().into_bytes(), "obj"),
+        other => return Err(format!("unknown format '{}', use glb or obj", other)),
+    };
+
+    let path = file_path(MESH_EXPORT_DIR, name, extension)?;
+    std::fs::create_dir_all(MESH_EXPORT_DIR).map_err(|e| format!("could not create {}: {}", MESH_EXPORT_DIR, e))?;
+    std::fs::write(&path, bytes).map_err(|e| format!("could not write {}: {}", path, e))?;
+    Ok(format!("{} triangles exported to {}", mesh.indices.len() / 3, path))
+}
+
+fn export_clipboard(clipboard: Option<&Clipboard>, name: Option<&str>) -> EditResult {
+    let clipboard = clipboard.ok_or("clipboard is empty")?;
+    let path = file_path(SCHEMATIC_DIR, name, "txt")?;
+    let text = clipboard.schematic.to_text()?;
+    std::fs::write(&path, text).map_err(|e| format!("could not write {}: {}", path, e))?;
+    Ok(format!("Clipboard exported to {}", path))
+}
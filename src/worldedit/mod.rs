@@ -0,0 +1,7 @@
+pub mod clipboard;
+pub mod commands;
+pub mod selection;
+pub mod shapes;
+
+pub use plugin::WorldEditPlugin;
+mod plugin;
@@ -0,0 +1,23 @@
+use bevy::prelude::*;
+
+use crate::player::gamemode::GameMode;
+use crate::worldedit::{
+    commands::{run_edit_commands, CurrentClipboard},
+    selection::{draw_selection, put_away_wand, wand_select, Selection}
+};
+
+pub struct WorldEditPlugin;
+
+impl Plugin for WorldEditPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<Selection>()
+            .init_resource::<CurrentClipboard>()
+            .add_systems(OnExit(GameMode::Creative), put_away_wand)
+            .add_systems(Update, (
+                run_edit_commands,
+                wand_select,
+                draw_selection
+            ).run_if(in_state(GameMode::Creative)));
+    }
+}
@@ -0,0 +1,80 @@
+use bevy::{prelude::*, window::PrimaryWindow};
+
+use crate::player::{body::{look_ray, Player}, camera::CameraController};
+use crate::voxel::{manager::ChunkManager, raycast::raycast};
+
+// Wand reach is longer than normal building so large areas can be marked from afar
+const WAND_REACH: f32 = 64.0;
+const SELECTION_COLOR: Color = Color::srgb(1.0, 0.8, 0.1);
+
+/// Two corners of the region the building tools work on, both inclusive
+#[derive(Resource, Default)]
+pub struct Selection {
+    pub pos1: Option<IVec3>,
+    pub pos2: Option<IVec3>,
+    // While active, clicks set corners instead of breaking and placing blocks
+    pub wand: bool,
+}
+
+impl Selection {
+    /// Minimum and maximum corner once both are set
+    pub fn bounds(&self) -> Option<(IVec3, IVec3)> {
+        let (a, b) = (self.pos1?, self.pos2?);
+        Some((a.min(b), a.max(b)))
+    }
+}
+
+pub fn volume(min: IVec3, max: IVec3) -> usize {
+    let size = (max - min + IVec3::ONE).as_uvec3();
+    size.x as usize * size.y as usize * size.z as usize
+}
+
+/// Run condition keeping normal block interaction off while the wand is out
+pub fn wand_inactive(selection: Res<Selection>) -> bool {
+    !selection.wand
+}
+
+/// Building tools are creative-only, so the wand can't stay out after leaving it
+pub fn put_away_wand(mut selection: ResMut<Selection>) {
+    selection.wand = false;
+}
+
+/// With the wand out, left click marks the first corner and right click the second
+pub fn wand_select(
+    mouse: Res<ButtonInput<MouseButton>>,
+    mut selection: ResMut<Selection>,
+    cm: Res<ChunkManager>,
+    player: Query<(&Transform, &CameraController), With<Player>>,
+    window: Query<&Window, With<PrimaryWindow>>,
+) {
+    if !selection.wand || window.single().is_ok_and(|w| w.cursor_options.visible) {
+        return;
+    }
+    let first = mouse.just_pressed(MouseButton::Left);
+    let second = mouse.just_pressed(MouseButton::Right);
+    if !first && !second {
+        return;
+    }
+
+    let Ok((body, controller)) = player.single() else { return };
+    let (eye, look) = look_ray(body, controller);
+    let Some(hit) = raycast(&cm, eye, look, WAND_REACH) else { return };
+
+    if first {
+        selection.pos1 = Some(hit.pos);
+        info!("📐 First corner set to {}", hit.pos);
+    } else {
+        selection.pos2 = Some(hit.pos);
+        info!("📐 Second corner set to {}", hit.pos);
+    }
+}
+
+pub fn draw_selection(selection: Res<Selection>, mut gizmos: Gizmos) {
+    let Some((min, max)) = selection.bounds() else { return };
+    let (min, max) = (min.as_vec3(), max.as_vec3() + Vec3::ONE);
+
+    gizmos.cuboid(
+        Transform::from_translation((min + max) / 2.0).with_scale(max - min),
+        SELECTION_COLOR,
+    );
+}
@@ -0,0 +1,44 @@
+use bevy::prelude::*;
+
+use crate::voxel::{
+    change::BlockChangeCause,
+    manager::{aabb_positions, sphere_positions, ChunkManager},
+    types::Block
+};
+
+/// Writes `block` at every position, returning how many blocks changed
+pub fn set_blocks(cm: &mut ChunkManager, positions: impl Iterator<Item = IVec3>, block: Block) -> usize {
+    positions
+        .filter(|pos| cm.set_block_world(*pos, block, BlockChangeCause::Edit).is_some_and(|old| old.id != block.id))
+        .count()
+}
+
+/// Everything strictly inside the box, leaving its six faces
+pub fn interior(min: IVec3, max: IVec3) -> impl Iterator<Item = IVec3> {
+    let empty = (max - min).cmplt(IVec3::splat(2)).any();
+    aabb_positions(min + IVec3::ONE, max - IVec3::ONE).filter(move |_| !empty)
+}
+
+/// The four vertical sides of the box
+pub fn walls(min: IVec3, max: IVec3) -> impl Iterator<Item = IVec3> {
+    aabb_positions(min, max)
+        .filter(move |p| p.x == min.x || p.x == max.x || p.z == min.z || p.z == max.z)
+}
+
+/// A ball, or only its one block thick shell when `hollow`
+pub fn sphere(centre: IVec3, radius: i32, hollow: bool) -> impl Iterator<Item = IVec3> {
+    let inner_sq = (radius - 1) * (radius - 1);
+    sphere_positions(centre, radius)
+        .filter(move |p| !hollow || p.distance_squared(centre) > inner_sq)
+}
+
+/// A vertical cylinder standing on `base`, or only its side when `hollow`
+pub fn cylinder(base: IVec3, radius: i32, height: i32, hollow: bool) -> impl Iterator<Item = IVec3> {
+    let (radius_sq, inner_sq) = (radius * radius, (radius - 1) * (radius - 1));
+    let top = base + IVec3::new(radius, height.max(1) - 1, radius);
+
+    aabb_positions(base - IVec3::new(radius, 0, radius), top).filter(move |p| {
+        let d = (*p - base).xz().length_squared();
+        d <= radius_sq && (!hollow || d > inner_sq)
+    })
+}